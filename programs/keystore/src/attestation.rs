@@ -0,0 +1,323 @@
+//! Parses just enough of the CTAP2 `attestationObject` CBOR structure to
+//! recover the compressed P-256 public key an authenticator attested to,
+//! so registration doesn't have to trust a raw pubkey handed over by the
+//! frontend. This is a bespoke decoder for the fixed shape WebAuthn
+//! produces, not a general-purpose CBOR implementation: indefinite-length
+//! items are rejected rather than supported, matching the strict-parsing
+//! style used for the secp256r1 precompile (`secp256r1.rs`) and for
+//! authenticatorData (`instructions/execute.rs`).
+
+use anchor_lang::prelude::*;
+use crate::error::KeystoreError;
+
+/// Derive the compressed secp256r1 pubkey and credentialId attested to by
+/// `attestation_object` (the raw CBOR bytes from `navigator.credentials.create`).
+pub fn parse_attestation_object(attestation_object: &[u8]) -> Result<([u8; 33], Vec<u8>)> {
+    let auth_data = find_auth_data(attestation_object)?;
+    parse_auth_data(&auth_data)
+}
+
+/// Walk the top-level CBOR map (`{"fmt": ..., "attStmt": ..., "authData": ...}`)
+/// looking for the `authData` byte string, skipping every other entry.
+fn find_auth_data(data: &[u8]) -> Result<Vec<u8>> {
+    let (major, count, mut pos) = read_header(data, 0)?;
+    require!(major == 5, KeystoreError::InvalidAttestationObject);
+
+    for _ in 0..count {
+        let (key, next_pos) = read_text_string(data, pos)?;
+        pos = next_pos;
+
+        if key == "authData" {
+            let (auth_data, _) = read_byte_string(data, pos)?;
+            return Ok(auth_data.to_vec());
+        }
+        pos = skip_value(data, pos)?;
+    }
+
+    Err(KeystoreError::InvalidAttestationObject.into())
+}
+
+/// Parse the fixed authenticatorData header, then the variable-length
+/// attestedCredentialData (present because the AT flag is set during
+/// registration): AAGUID || credentialIdLength || credentialId || COSE_Key.
+fn parse_auth_data(auth_data: &[u8]) -> Result<([u8; 33], Vec<u8>)> {
+    require!(auth_data.len() >= 37, KeystoreError::InvalidAttestationObject);
+
+    let flags = auth_data[32];
+    require!(flags & 0x40 != 0, KeystoreError::InvalidAttestationObject);
+
+    let mut pos = 37 + 16; // skip the fixed header and the AAGUID
+    require!(auth_data.len() >= pos + 2, KeystoreError::InvalidAttestationObject);
+
+    let cred_id_len = u16::from_be_bytes([auth_data[pos], auth_data[pos + 1]]) as usize;
+    pos += 2;
+    require!(auth_data.len() >= pos + cred_id_len, KeystoreError::InvalidAttestationObject);
+    let credential_id = auth_data[pos..pos + cred_id_len].to_vec();
+    pos += cred_id_len;
+
+    let pubkey = parse_cose_ec2_key(auth_data, pos)?;
+    Ok((pubkey, credential_id))
+}
+
+/// Parse a COSE_Key CBOR map for an EC2 P-256 key (kty=2, alg=ES256, crv=P-256)
+/// and compress it to 33 bytes: `0x02`/`0x03` prefix (even/odd y) || x.
+fn parse_cose_ec2_key(data: &[u8], start: usize) -> Result<[u8; 33]> {
+    let (major, count, mut pos) = read_header(data, start)?;
+    require!(major == 5, KeystoreError::InvalidAttestationObject);
+
+    let mut x: Option<[u8; 32]> = None;
+    let mut y: Option<[u8; 32]> = None;
+
+    for _ in 0..count {
+        let (label, next_pos) = read_int(data, pos)?;
+        pos = next_pos;
+
+        match label {
+            1 => {
+                let (kty, next_pos) = read_int(data, pos)?;
+                require!(kty == 2, KeystoreError::InvalidAttestationObject); // EC2
+                pos = next_pos;
+            }
+            3 => {
+                let (alg, next_pos) = read_int(data, pos)?;
+                require!(alg == -7, KeystoreError::InvalidAttestationObject); // ES256
+                pos = next_pos;
+            }
+            -1 => {
+                let (crv, next_pos) = read_int(data, pos)?;
+                require!(crv == 1, KeystoreError::InvalidAttestationObject); // P-256
+                pos = next_pos;
+            }
+            -2 => {
+                let (coord, next_pos) = read_byte_string(data, pos)?;
+                require!(coord.len() == 32, KeystoreError::InvalidAttestationObject);
+                let mut buf = [0u8; 32];
+                buf.copy_from_slice(coord);
+                x = Some(buf);
+                pos = next_pos;
+            }
+            -3 => {
+                let (coord, next_pos) = read_byte_string(data, pos)?;
+                require!(coord.len() == 32, KeystoreError::InvalidAttestationObject);
+                let mut buf = [0u8; 32];
+                buf.copy_from_slice(coord);
+                y = Some(buf);
+                pos = next_pos;
+            }
+            _ => pos = skip_value(data, pos)?,
+        }
+    }
+
+    let x = x.ok_or(KeystoreError::InvalidAttestationObject)?;
+    let y = y.ok_or(KeystoreError::InvalidAttestationObject)?;
+
+    let mut pubkey = [0u8; 33];
+    pubkey[0] = if y[31] % 2 == 0 { 0x02 } else { 0x03 };
+    pubkey[1..].copy_from_slice(&x);
+    Ok(pubkey)
+}
+
+/// Read a CBOR item header at `pos`: returns (major type, argument, position
+/// of the first byte after the header). Indefinite-length items (additional
+/// info 31) are rejected - CTAP2 authenticators emit canonical, definite
+/// length CBOR only.
+fn read_header(data: &[u8], pos: usize) -> Result<(u8, u64, usize)> {
+    require!(pos < data.len(), KeystoreError::InvalidAttestationObject);
+    let first = data[pos];
+    let major = first >> 5;
+    let additional = first & 0x1f;
+
+    match additional {
+        0..=23 => Ok((major, additional as u64, pos + 1)),
+        24 => {
+            require!(data.len() > pos + 1, KeystoreError::InvalidAttestationObject);
+            Ok((major, data[pos + 1] as u64, pos + 2))
+        }
+        25 => {
+            require!(data.len() > pos + 2, KeystoreError::InvalidAttestationObject);
+            let arg = u16::from_be_bytes([data[pos + 1], data[pos + 2]]) as u64;
+            Ok((major, arg, pos + 3))
+        }
+        26 => {
+            require!(data.len() > pos + 4, KeystoreError::InvalidAttestationObject);
+            let arg = u32::from_be_bytes([data[pos + 1], data[pos + 2], data[pos + 3], data[pos + 4]]) as u64;
+            Ok((major, arg, pos + 5))
+        }
+        27 => {
+            require!(data.len() > pos + 8, KeystoreError::InvalidAttestationObject);
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[pos + 1..pos + 9]);
+            Ok((major, u64::from_be_bytes(bytes), pos + 9))
+        }
+        _ => Err(KeystoreError::InvalidAttestationObject.into()), // indefinite length, unsupported
+    }
+}
+
+/// Read a signed integer (CBOR major type 0 or 1).
+fn read_int(data: &[u8], pos: usize) -> Result<(i64, usize)> {
+    let (major, arg, next_pos) = read_header(data, pos)?;
+    match major {
+        0 => Ok((arg as i64, next_pos)),
+        1 => Ok((-1 - arg as i64, next_pos)),
+        _ => Err(KeystoreError::InvalidAttestationObject.into()),
+    }
+}
+
+/// Read a text string (CBOR major type 3).
+fn read_text_string(data: &[u8], pos: usize) -> Result<(&str, usize)> {
+    let (major, len, next_pos) = read_header(data, pos)?;
+    require!(major == 3, KeystoreError::InvalidAttestationObject);
+    let len = len as usize;
+    require!(data.len() >= next_pos + len, KeystoreError::InvalidAttestationObject);
+    let s = std::str::from_utf8(&data[next_pos..next_pos + len])
+        .map_err(|_| KeystoreError::InvalidAttestationObject)?;
+    Ok((s, next_pos + len))
+}
+
+/// Read a byte string (CBOR major type 2).
+fn read_byte_string(data: &[u8], pos: usize) -> Result<(&[u8], usize)> {
+    let (major, len, next_pos) = read_header(data, pos)?;
+    require!(major == 2, KeystoreError::InvalidAttestationObject);
+    let len = len as usize;
+    require!(data.len() >= next_pos + len, KeystoreError::InvalidAttestationObject);
+    Ok((&data[next_pos..next_pos + len], next_pos + len))
+}
+
+/// Skip over a single CBOR value of any type, returning the position right
+/// after it. Used for map/array entries whose contents we don't need.
+fn skip_value(data: &[u8], pos: usize) -> Result<usize> {
+    let (major, arg, next_pos) = read_header(data, pos)?;
+    match major {
+        0 | 1 => Ok(next_pos), // int value is the header argument itself
+        2 | 3 => {
+            let len = arg as usize;
+            require!(data.len() >= next_pos + len, KeystoreError::InvalidAttestationObject);
+            Ok(next_pos + len)
+        }
+        4 => {
+            let mut pos = next_pos;
+            for _ in 0..arg {
+                pos = skip_value(data, pos)?;
+            }
+            Ok(pos)
+        }
+        5 => {
+            let mut pos = next_pos;
+            for _ in 0..arg * 2 {
+                pos = skip_value(data, pos)?;
+            }
+            Ok(pos)
+        }
+        6 => skip_value(data, next_pos), // tag: skip the tagged value
+        7 => Ok(next_pos),               // simple/float: fully consumed by the header
+        _ => Err(KeystoreError::InvalidAttestationObject.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cbor_uint_header(major: u8, arg: u64) -> Vec<u8> {
+        if arg < 24 {
+            vec![(major << 5) | arg as u8]
+        } else {
+            vec![(major << 5) | 24, arg as u8]
+        }
+    }
+
+    fn cbor_text(s: &str) -> Vec<u8> {
+        let mut v = cbor_uint_header(3, s.len() as u64);
+        v.extend_from_slice(s.as_bytes());
+        v
+    }
+
+    fn cbor_bytes(b: &[u8]) -> Vec<u8> {
+        let mut v = cbor_uint_header(2, b.len() as u64);
+        v.extend_from_slice(b);
+        v
+    }
+
+    fn cbor_map_header(count: u64) -> Vec<u8> {
+        cbor_uint_header(5, count)
+    }
+
+    fn cbor_uint(n: u64) -> Vec<u8> {
+        cbor_uint_header(0, n)
+    }
+
+    /// CBOR negative int: encodes `-(n + 1)`, used for COSE_Key's negative labels.
+    fn cbor_negint(n: u64) -> Vec<u8> {
+        cbor_uint_header(1, n)
+    }
+
+    /// Build a well-formed attestationObject wrapping a COSE EC2 P-256 key
+    /// with the given x/y coordinates and credentialId, matching the shape
+    /// `navigator.credentials.create` produces.
+    fn build_attestation_object(x: [u8; 32], y: [u8; 32], credential_id: &[u8]) -> Vec<u8> {
+        let mut cose = cbor_map_header(5);
+        cose.extend(cbor_uint(1));
+        cose.extend(cbor_uint(2)); // kty: EC2
+        cose.extend(cbor_uint(3));
+        cose.extend(cbor_negint(6)); // alg: ES256 (-7)
+        cose.extend(cbor_negint(0));
+        cose.extend(cbor_uint(1)); // crv: P-256
+        cose.extend(cbor_negint(1));
+        cose.extend(cbor_bytes(&x));
+        cose.extend(cbor_negint(2));
+        cose.extend(cbor_bytes(&y));
+
+        let mut auth_data = vec![0u8; 32]; // rpIdHash
+        auth_data.push(0x40); // flags: AT (attested credential data present)
+        auth_data.extend_from_slice(&0u32.to_be_bytes()); // signCount
+        auth_data.extend_from_slice(&[0u8; 16]); // aaguid
+        auth_data.extend_from_slice(&(credential_id.len() as u16).to_be_bytes());
+        auth_data.extend_from_slice(credential_id);
+        auth_data.extend_from_slice(&cose);
+
+        let mut obj = cbor_map_header(3);
+        obj.extend(cbor_text("fmt"));
+        obj.extend(cbor_text("none"));
+        obj.extend(cbor_text("attStmt"));
+        obj.extend(cbor_map_header(0));
+        obj.extend(cbor_text("authData"));
+        obj.extend(cbor_bytes(&auth_data));
+        obj
+    }
+
+    #[test]
+    fn test_parse_attestation_object_round_trips() {
+        let x = [0x11u8; 32];
+        let y = [0u8; 32]; // even last byte -> 0x02 prefix
+        let credential_id = vec![0xABu8; 4];
+
+        let obj = build_attestation_object(x, y, &credential_id);
+        let (pubkey, parsed_credential_id) = parse_attestation_object(&obj).unwrap();
+
+        assert_eq!(pubkey[0], 0x02);
+        assert_eq!(&pubkey[1..], &x);
+        assert_eq!(parsed_credential_id, credential_id);
+    }
+
+    #[test]
+    fn test_parse_attestation_object_rejects_indefinite_length() {
+        // Major type 5 (map) with additional info 31: indefinite-length,
+        // unsupported by this strict, definite-length-only parser.
+        let obj = vec![0xbf];
+        assert!(parse_attestation_object(&obj).is_err());
+    }
+
+    #[test]
+    fn test_parse_attestation_object_rejects_truncated_input() {
+        let obj = build_attestation_object([0x11u8; 32], [0u8; 32], &[0xABu8; 4]);
+        assert!(parse_attestation_object(&obj[..obj.len() - 10]).is_err());
+    }
+
+    #[test]
+    fn test_parse_attestation_object_rejects_missing_auth_data() {
+        let mut obj = cbor_map_header(1);
+        obj.extend(cbor_text("fmt"));
+        obj.extend(cbor_text("none"));
+        assert!(parse_attestation_object(&obj).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use anchor_lang::prelude::*;
+use crate::error::KeystoreError;
+use crate::secp256r1::resolve_instruction_data;
+
+// Native ed25519 program ID
+pub const ED25519_PROGRAM_ID: Pubkey = pubkey!("Ed25519SigVerify111111111111111111111111111");
+
+/// ed25519 native program instruction data format:
+///
+/// Header (2 bytes):
+///   - u8: number of signatures
+///   - u8: padding
+///
+/// Per-signature offsets (14 bytes - all u16), repeated `num_signatures` times:
+///   - u16: signature_offset
+///   - u16: signature_instruction_index (0xFFFF = current instruction)
+///   - u16: public_key_offset
+///   - u16: public_key_instruction_index (0xFFFF = current instruction)
+///   - u16: message_data_offset
+///   - u16: message_data_size
+///   - u16: message_instruction_index (0xFFFF = current instruction)
+///
+/// Data section (following header + offsets), one chunk per signature:
+///   - pubkey: 32 bytes
+///   - signature: 64 bytes
+///   - message: variable length
+
+/// Offsets for a single signature within an ed25519 program instruction.
+#[derive(Debug, Clone, Copy)]
+pub struct Ed25519SignatureOffsets {
+    pub signature_offset: u16,
+    pub signature_ix_index: u16,
+    pub pubkey_offset: u16,
+    pub pubkey_ix_index: u16,
+    pub message_offset: u16,
+    pub message_size: u16,
+    pub message_ix_index: u16,
+}
+
+impl Ed25519SignatureOffsets {
+    const ENCODED_SIZE: usize = 14;
+
+    fn parse(data: &[u8]) -> Self {
+        Self {
+            signature_offset: u16::from_le_bytes([data[0], data[1]]),
+            signature_ix_index: u16::from_le_bytes([data[2], data[3]]),
+            pubkey_offset: u16::from_le_bytes([data[4], data[5]]),
+            pubkey_ix_index: u16::from_le_bytes([data[6], data[7]]),
+            message_offset: u16::from_le_bytes([data[8], data[9]]),
+            message_size: u16::from_le_bytes([data[10], data[11]]),
+            message_ix_index: u16::from_le_bytes([data[12], data[13]]),
+        }
+    }
+
+    /// Extract signature from instruction data or referenced instruction
+    pub fn extract_signature(
+        &self,
+        instruction_data: &[u8],
+        instructions_sysvar: &AccountInfo,
+    ) -> Result<Vec<u8>> {
+        let data = resolve_instruction_data(
+            self.signature_ix_index,
+            instruction_data,
+            instructions_sysvar,
+        )?;
+        let start = self.signature_offset as usize;
+        let end = start
+            .checked_add(64)
+            .ok_or(KeystoreError::InvalidDataOffsets)?;
+        require!(data.len() >= end, KeystoreError::InvalidDataOffsets);
+        Ok(data[start..end].to_vec())
+    }
+
+    /// Extract public key from instruction data or referenced instruction
+    pub fn extract_pubkey(
+        &self,
+        instruction_data: &[u8],
+        instructions_sysvar: &AccountInfo,
+    ) -> Result<Vec<u8>> {
+        let data = resolve_instruction_data(
+            self.pubkey_ix_index,
+            instruction_data,
+            instructions_sysvar,
+        )?;
+        let start = self.pubkey_offset as usize;
+        let end = start
+            .checked_add(32) // ed25519 key
+            .ok_or(KeystoreError::InvalidDataOffsets)?;
+        require!(data.len() >= end, KeystoreError::InvalidDataOffsets);
+        Ok(data[start..end].to_vec())
+    }
+
+    /// Extract message from instruction data or referenced instruction
+    pub fn extract_message(
+        &self,
+        instruction_data: &[u8],
+        instructions_sysvar: &AccountInfo,
+    ) -> Result<Vec<u8>> {
+        let data = resolve_instruction_data(
+            self.message_ix_index,
+            instruction_data,
+            instructions_sysvar,
+        )?;
+        let start = self.message_offset as usize;
+        let end = start
+            .checked_add(self.message_size as usize)
+            .ok_or(KeystoreError::InvalidDataOffsets)?;
+        require!(data.len() >= end, KeystoreError::InvalidDataOffsets);
+        Ok(data[start..end].to_vec())
+    }
+}
+
+#[derive(Debug)]
+pub struct Ed25519InstructionData {
+    pub num_signatures: u8,
+    pub offsets: Vec<Ed25519SignatureOffsets>,
+}
+
+impl Ed25519InstructionData {
+    /// Parse ed25519 instruction data (same offset-table shape as the
+    /// secp256r1 precompile, see [`crate::secp256r1::Secp256r1InstructionData`]).
+    pub fn try_from_slice(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() >= 2 + Ed25519SignatureOffsets::ENCODED_SIZE,
+            KeystoreError::InvalidInstructionDataSize
+        );
+
+        let num_signatures = data[0];
+        require!(num_signatures != 0, KeystoreError::InvalidInstructionDataSize);
+
+        let offsets_len = (num_signatures as usize)
+            .checked_mul(Ed25519SignatureOffsets::ENCODED_SIZE)
+            .ok_or(KeystoreError::InvalidInstructionDataSize)?;
+        let header_len = 2usize
+            .checked_add(offsets_len)
+            .ok_or(KeystoreError::InvalidInstructionDataSize)?;
+        require!(
+            data.len() >= header_len,
+            KeystoreError::InvalidInstructionDataSize
+        );
+
+        let mut offsets = Vec::with_capacity(num_signatures as usize);
+        for i in 0..num_signatures as usize {
+            let start = 2 + i * Ed25519SignatureOffsets::ENCODED_SIZE;
+            let end = start + Ed25519SignatureOffsets::ENCODED_SIZE;
+            offsets.push(Ed25519SignatureOffsets::parse(&data[start..end]));
+        }
+
+        Ok(Self {
+            num_signatures,
+            offsets,
+        })
+    }
+}
+
+/// Find the ed25519 native program instruction preceding the current
+/// instruction in this transaction.
+fn find_ed25519_instruction(
+    instructions_sysvar: &AccountInfo,
+) -> Result<anchor_lang::solana_program::instruction::Instruction> {
+    use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
+
+    let current_idx = ix_sysvar::load_current_index_checked(instructions_sysvar)
+        .map_err(|_| KeystoreError::InvalidEd25519Instruction)?;
+
+    for i in (0..current_idx).rev() {
+        let ix = ix_sysvar::load_instruction_at_checked(i as usize, instructions_sysvar)
+            .map_err(|_| KeystoreError::InvalidEd25519Instruction)?;
+
+        if ix.program_id == ED25519_PROGRAM_ID {
+            return Ok(ix);
+        }
+    }
+
+    Err(KeystoreError::MissingEd25519Instruction.into())
+}
+
+/// Verify an ed25519 signature using the native ed25519 program,
+/// mirroring [`crate::secp256r1::verify_secp256r1_signature`].
+pub fn verify_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    expected_pubkey: &[u8; 32],
+    expected_message: &[u8],
+    expected_signature: &[u8; 64],
+) -> Result<()> {
+    let ix = find_ed25519_instruction(instructions_sysvar)?;
+    let parsed = Ed25519InstructionData::try_from_slice(&ix.data)?;
+
+    for offsets in &parsed.offsets {
+        let sig = offsets.extract_signature(&ix.data, instructions_sysvar)?;
+        let pk = offsets.extract_pubkey(&ix.data, instructions_sysvar)?;
+        let msg_data = offsets.extract_message(&ix.data, instructions_sysvar)?;
+
+        if pk == expected_pubkey.as_slice()
+            && sig == expected_signature.as_slice()
+            && msg_data == expected_message
+        {
+            return Ok(());
+        }
+    }
+
+    Err(KeystoreError::SignatureVerificationFailed.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_instruction_data() {
+        let data = vec![
+            1, // num_sigs
+            0, // padding
+            13, 0, // sig_offset
+            0xFF, 0xFF, // sig_ix
+            77, 0, // pk_offset
+            0xFF, 0xFF, // pk_ix
+            109, 0, // msg_offset
+            32, 0, // msg_size
+            0xFF, 0xFF, // msg_ix
+        ];
+
+        let parsed = Ed25519InstructionData::try_from_slice(&data).unwrap();
+        assert_eq!(parsed.num_signatures, 1);
+        assert_eq!(parsed.offsets.len(), 1);
+        assert_eq!(parsed.offsets[0].signature_offset, 13);
+        assert_eq!(parsed.offsets[0].message_size, 32);
+    }
+
+    #[test]
+    fn test_parse_multi_signature_instruction_data() {
+        let mut data = vec![2, 0]; // num_sigs = 2, padding
+        for sig_offset in [16u16, 96u16] {
+            data.extend_from_slice(&sig_offset.to_le_bytes()); // signature_offset
+            data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // signature_ix_index
+            data.extend_from_slice(&(sig_offset + 64).to_le_bytes()); // pubkey_offset
+            data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // pubkey_ix_index
+            data.extend_from_slice(&(sig_offset + 64 + 32).to_le_bytes()); // message_offset
+            data.extend_from_slice(&32u16.to_le_bytes()); // message_size
+            data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // message_ix_index
+        }
+
+        let parsed = Ed25519InstructionData::try_from_slice(&data).unwrap();
+        assert_eq!(parsed.num_signatures, 2);
+        assert_eq!(parsed.offsets.len(), 2);
+        assert_eq!(parsed.offsets[0].signature_offset, 16);
+        assert_eq!(parsed.offsets[1].signature_offset, 96);
+    }
+
+    #[test]
+    fn test_zero_signatures_rejected() {
+        let data = vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert!(Ed25519InstructionData::try_from_slice(&data).is_err());
+    }
+
+    #[test]
+    fn test_too_short_rejected() {
+        let data = vec![1, 0, 0, 0]; // header claims 1 sig but no offset struct follows
+        assert!(Ed25519InstructionData::try_from_slice(&data).is_err());
+    }
+}
@@ -24,5 +24,43 @@ pub enum KeystoreError {
     InvalidAccountData,
     #[msg("Insufficient funds")]
     InsufficientFunds,
+    #[msg("Invalid device name")]
+    InvalidDeviceName,
+    #[msg("Invalid public key format")]
+    InvalidPublicKeyFormat,
+    #[msg("Invalid WebAuthn data")]
+    InvalidWebAuthnData,
+    #[msg("No matching precompile instruction found in this transaction")]
+    MissingSecp256r1Instruction,
+    #[msg("Invalid ed25519 instruction format")]
+    InvalidEd25519Instruction,
+    #[msg("No matching ed25519 instruction found in this transaction")]
+    MissingEd25519Instruction,
+    #[msg("Precompile offset points outside the referenced instruction's data")]
+    InvalidDataOffsets,
+    #[msg("Precompile instruction index does not reference a real instruction")]
+    InvalidInstructionIndex,
+    #[msg("Precompile instruction data does not match its declared signature count")]
+    InvalidInstructionDataSize,
+    #[msg("Unsupported key type for this operation")]
+    UnsupportedKeyType,
+    #[msg("Signed message has expired")]
+    MessageExpired,
+    #[msg("Authenticator data is malformed or too short")]
+    InvalidAuthenticatorData,
+    #[msg("User presence was not asserted by the authenticator")]
+    UserPresenceRequired,
+    #[msg("Authenticator signature counter did not increase; possible cloned credential")]
+    SignCountReplayed,
+    #[msg("Execute batch must contain between 1 and 8 actions")]
+    TooManyActions,
+    #[msg("Send would exceed the identity's rolling spend limit")]
+    SpendLimitExceeded,
+    #[msg("clientDataJSON origin does not match the identity's allowed origin")]
+    OriginMismatch,
+    #[msg("Malformed or unsupported CTAP2 attestationObject")]
+    InvalidAttestationObject,
+    #[msg("SendCrossChain must be submitted via execute_cross_chain")]
+    CrossChainRequiresDedicatedInstruction,
 }
 
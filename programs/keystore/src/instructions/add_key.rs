@@ -18,33 +18,30 @@ pub struct AddKey<'info> {
 pub fn handler(
     ctx: Context<AddKey>,
     new_pubkey: [u8; 33],
+    key_type: KeyType,
     device_name: String,
 ) -> Result<()> {
     let identity = &mut ctx.accounts.identity;
     let clock = Clock::get()?;
-    
+
     // Validate input
     require!(
         device_name.len() <= 32,
         KeystoreError::InvalidArgument
     );
-    
+
     require!(
         !device_name.is_empty(),
         KeystoreError::InvalidArgument
     );
-    
-    // Validate pubkey (compressed secp256r1: must start with 0x02 or 0x03)
-    require!(
-        new_pubkey[0] == 0x02 || new_pubkey[0] == 0x03,
-        KeystoreError::InvalidPublicKey
-    );
-    
+
+    RegisteredKey::validate_pubkey(key_type, &new_pubkey)?;
+
     require!(
         identity.keys.len() < Identity::MAX_KEYS,
         KeystoreError::MaxKeysReached
     );
-    
+
     // Check for duplicate public keys
     for key in &identity.keys {
         require!(
@@ -52,13 +49,15 @@ pub fn handler(
             KeystoreError::DuplicateKey
         );
     }
-    
+
     identity.keys.push(RegisteredKey {
+        key_type,
         pubkey: new_pubkey,
         name: device_name,
         added_at: clock.unix_timestamp,
+        last_sign_count: 0,
     });
-    
+
     msg!("Key added. Total keys: {}", identity.keys.len());
     Ok(())
 }
@@ -29,33 +29,38 @@ pub struct CreateIdentity<'info> {
 pub fn handler(
     ctx: Context<CreateIdentity>,
     pubkey: [u8; 33],
+    key_type: KeyType,
     device_name: String,
+    allowed_origin_hash: [u8; 32],
 ) -> Result<()> {
     let identity = &mut ctx.accounts.identity;
     let clock = Clock::get()?;
-    
+
     // Validate input
     require!(
         device_name.len() <= 32 && !device_name.is_empty(),
         KeystoreError::InvalidDeviceName
     );
-    
-    // Validate pubkey (compressed secp256r1: must start with 0x02 or 0x03)
-    require!(
-        pubkey[0] == 0x02 || pubkey[0] == 0x03,
-        KeystoreError::InvalidPublicKeyFormat
-    );
-    
+
+    RegisteredKey::validate_pubkey(key_type, &pubkey)?;
+
     identity.bump = ctx.bumps.identity;
     identity.vault_bump = ctx.bumps.vault;
     identity.threshold = 1;
     identity.nonce = 0;
+    identity.spend_limit = 0;
+    identity.window_secs = 0;
+    identity.window_start = clock.unix_timestamp;
+    identity.spent_in_window = 0;
+    identity.allowed_origin_hash = allowed_origin_hash;
     identity.keys = vec![RegisteredKey {
+        key_type,
         pubkey,
         name: device_name,
         added_at: clock.unix_timestamp,
+        last_sign_count: 0,
     }];
-    
+
     msg!("Identity created with 1 key");
     Ok(())
 }
@@ -6,13 +6,30 @@ use crate::state::*;
 use crate::error::KeystoreError;
 use crate::{Action, SignatureData, WebAuthnSignatureData};
 use crate::secp256r1;
+use crate::ed25519;
 use std::collections::HashSet;
 
+/// Domain separator for execute messages, so a signature over this payload
+/// can never be replayed against a different instruction or program version.
+/// Bump the version suffix whenever the signed payload shape changes, so
+/// signatures gathered under an older format are rejected outright.
+const EXECUTE_DOMAIN_TAG: &[u8] = b"keystore:execute:v2";
+
+/// One-byte discriminant for the cluster this program is deployed to, so a
+/// signature gathered on devnet can never replay against mainnet-beta (or
+/// vice versa). Update this when cutting a deployment for a new cluster.
+const CLUSTER_DISCRIMINANT: u8 = 0;
+
+/// Cap on the number of actions approved by a single signature, so a batch
+/// can't grow the transaction past compute/size limits or bury an unbounded
+/// amount of `Action::Send`s behind one signing prompt.
+pub const MAX_ACTIONS: usize = 8;
+
 #[derive(Accounts)]
 pub struct Execute<'info> {
     #[account(mut)]
     pub identity: Account<'info, Identity>,
-    
+
     #[account(
         mut,
         seeds = [b"vault", identity.key().as_ref()],
@@ -20,37 +37,42 @@ pub struct Execute<'info> {
     )]
     /// CHECK: PDA vault
     pub vault: SystemAccount<'info>,
-    
-    /// CHECK: Optional recipient for Send action
-    #[account(mut)]
-    pub recipient: Option<AccountInfo<'info>>,
-    
+
     /// CHECK: Instructions sysvar for verifying secp256r1 precompile
     #[account(address = ix_sysvar::ID)]
     pub instructions: AccountInfo<'info>,
-    
+
     pub system_program: Program<'info, System>,
+    // `Action::Send` recipients are passed via `remaining_accounts`, looked
+    // up by the `to` pubkey in each action, so a batch can target more than
+    // one recipient under a single signature.
 }
 
 pub fn handler(
     ctx: Context<Execute>,
-    action: Action,
+    actions: Vec<Action>,
     sigs: Vec<SignatureData>,
+    expires_at: i64,
 ) -> Result<()> {
-    let identity = &mut ctx.accounts.identity;
-    
+    let identity_key = ctx.accounts.identity.key();
+
+    require!(
+        !actions.is_empty() && actions.len() <= MAX_ACTIONS,
+        KeystoreError::TooManyActions
+    );
+
     // Validate signatures array
     require!(
         !sigs.is_empty(),
         KeystoreError::ThresholdNotMet
     );
-    
+
     // Check threshold
     require!(
-        sigs.len() >= identity.threshold as usize,
+        sigs.len() >= ctx.accounts.identity.threshold as usize,
         KeystoreError::ThresholdNotMet
     );
-    
+
     // Check for duplicate key indices
     let mut used_keys = std::collections::HashSet::new();
     for sig in &sigs {
@@ -59,232 +81,358 @@ pub fn handler(
             KeystoreError::SignatureVerificationFailed
         );
     }
-    
-    // Build message that was signed (action + nonce)
-    let message = build_message(&action, identity.nonce)?;
-    
-    // Verify each signature via secp256r1 precompile introspection
-    for sig in &sigs {
-        let key = identity.keys
+
+    // Reject signatures over a message that has already expired
+    require!(
+        expires_at > Clock::get()?.unix_timestamp,
+        KeystoreError::MessageExpired
+    );
+
+    // Build message that was signed (domain tag + program id + identity + cluster + nonce + expiry + actions).
+    // Binding the current nonce means a captured signature can never be replayed:
+    // the stored nonce advances on success, so the same message can never verify twice.
+    let message = build_message(&actions, &identity_key, ctx.accounts.identity.nonce, expires_at)?;
+
+    verify_signatures(
+        &ctx.accounts.instructions,
+        &ctx.accounts.identity.keys,
+        &sigs,
+        &message,
+    )?;
+
+    // Increment nonce exactly once for the whole batch (before execution to
+    // prevent reentrancy), then execute every action atomically.
+    ctx.accounts.identity.nonce += 1;
+
+    execute_actions(
+        &mut ctx.accounts.identity,
+        &ctx.accounts.vault,
+        &ctx.accounts.system_program,
+        ctx.remaining_accounts,
+        actions,
+    )
+}
+
+/// Verify every claimed signature over `message` against its registered key.
+///
+/// Secp256r1 signers are verified together in a single pass over the one
+/// secp256r1 precompile instruction via
+/// [`secp256r1::verify_secp256r1_signatures`], instead of re-scanning that
+/// instruction once per signer. Ed25519 signers are still checked one at a
+/// time through the native ed25519 program, since there is no batched
+/// equivalent for that precompile.
+pub(crate) fn verify_signatures(
+    instructions_sysvar: &AccountInfo,
+    keys: &[RegisteredKey],
+    sigs: &[SignatureData],
+    message: &[u8],
+) -> Result<()> {
+    let mut secp_triples: Vec<(&[u8; 33], &[u8], &[u8; 64])> = Vec::new();
+
+    for sig in sigs {
+        let key = keys
             .get(sig.key_index as usize)
             .ok_or(KeystoreError::InvalidKeyIndex)?;
-        
-        secp256r1::verify_secp256r1_signature(
-            &ctx.accounts.instructions,
-            &key.pubkey,
-            &message,
-            &sig.signature,
-        )?;
-    }
-    
-    // Increment nonce (before execution to prevent reentrancy)
-    identity.nonce += 1;
-    
-    // Execute action
-    match action {
-        Action::Send { to, lamports } => {
-            let recipient = ctx.accounts.recipient
-                .as_ref()
-                .ok_or(KeystoreError::InvalidAccountData)?;
-            
-            if recipient.key() != to {
-                return Err(KeystoreError::InvalidAccountData.into());
-            }
-            
-            // Check vault has sufficient balance
-            let vault_balance = ctx.accounts.vault.lamports();
-            require!(
-                vault_balance >= lamports,
-                KeystoreError::InsufficientFunds
-            );
-            
-            // Ensure we maintain rent exemption (if needed)
-            let rent = Rent::get()?;
-            let min_balance = rent.minimum_balance(0);
-            require!(
-                vault_balance.saturating_sub(lamports) >= min_balance || lamports == vault_balance,
-                KeystoreError::InsufficientFunds
-            );
-            
-            let identity_key = identity.key();
-            let seeds: &[&[u8]] = &[
-                b"vault",
-                identity_key.as_ref(),
-                &[identity.vault_bump],
-            ];
-            
-            system_program::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.system_program.to_account_info(),
-                    system_program::Transfer {
-                        from: ctx.accounts.vault.to_account_info(),
-                        to: recipient.to_account_info(),
-                    },
-                    &[seeds],
-                ),
-                lamports,
-            )?;
-            
-            msg!("Sent {} lamports to {}", lamports, to);
-        }
-        Action::SetThreshold { threshold } => {
-            require!(threshold > 0, KeystoreError::InvalidThreshold);
-            require!(
-                threshold as usize <= identity.keys.len(),
-                KeystoreError::InvalidThreshold
-            );
-            identity.threshold = threshold;
-            msg!("Threshold set to {}", threshold);
+
+        match key.key_type {
+            KeyType::Secp256r1 => secp_triples.push((&key.pubkey, message, &sig.signature)),
+            KeyType::Ed25519 => ed25519::verify_ed25519_signature(
+                instructions_sysvar,
+                &key.ed25519_pubkey(),
+                message,
+                &sig.signature,
+            )?,
         }
     }
-    
+
+    // All claimed secp256r1 signers must be found packed into the one
+    // secp256r1 precompile instruction preceding this one.
+    if !secp_triples.is_empty() {
+        secp256r1::verify_secp256r1_signatures(instructions_sysvar, &secp_triples, secp_triples.len())?;
+    }
+
     Ok(())
 }
 
-fn build_message(action: &Action, nonce: u64) -> Result<Vec<u8>> {
-    let mut message = action.try_to_vec()?;
+pub(crate) fn build_message(actions: &Vec<Action>, identity: &Pubkey, nonce: u64, expires_at: i64) -> Result<Vec<u8>> {
+    let mut message = EXECUTE_DOMAIN_TAG.to_vec();
+    message.extend_from_slice(crate::ID.as_ref());
+    message.extend_from_slice(identity.as_ref());
+    message.push(CLUSTER_DISCRIMINANT);
     message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&expires_at.to_le_bytes());
+    message.extend_from_slice(&actions.try_to_vec()?);
     Ok(message)
 }
 
+/// Execute a batch of actions against `identity`'s vault, all-or-nothing:
+/// any action failing (e.g. insufficient funds) reverts the whole instruction,
+/// since Anchor propagates the `?` all the way out of the top-level handler.
+fn execute_actions<'info>(
+    identity: &mut Account<'info, Identity>,
+    vault: &SystemAccount<'info>,
+    system_program: &Program<'info, System>,
+    remaining_accounts: &[AccountInfo<'info>],
+    actions: Vec<Action>,
+) -> Result<()> {
+    for action in actions {
+        match action {
+            Action::Send { to, lamports } => {
+                let recipient = remaining_accounts
+                    .iter()
+                    .find(|account| account.key() == to)
+                    .ok_or(KeystoreError::InvalidAccountData)?;
+
+                // Check vault has sufficient balance
+                let vault_balance = vault.lamports();
+                require!(
+                    vault_balance >= lamports,
+                    KeystoreError::InsufficientFunds
+                );
+
+                // Ensure we maintain rent exemption (if needed)
+                let rent = Rent::get()?;
+                let min_balance = rent.minimum_balance(0);
+                require!(
+                    vault_balance.saturating_sub(lamports) >= min_balance || lamports == vault_balance,
+                    KeystoreError::InsufficientFunds
+                );
+
+                // Enforce the rolling spend-limit policy, if one is set
+                if identity.spend_limit > 0 {
+                    let now = Clock::get()?.unix_timestamp;
+                    if now - identity.window_start >= identity.window_secs {
+                        identity.window_start = now;
+                        identity.spent_in_window = 0;
+                    }
+                    require!(
+                        identity.spent_in_window.saturating_add(lamports) <= identity.spend_limit,
+                        KeystoreError::SpendLimitExceeded
+                    );
+                    identity.spent_in_window += lamports;
+                }
+
+                let identity_key = identity.key();
+                let seeds: &[&[u8]] = &[
+                    b"vault",
+                    identity_key.as_ref(),
+                    &[identity.vault_bump],
+                ];
+
+                system_program::transfer(
+                    CpiContext::new_with_signer(
+                        system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: vault.to_account_info(),
+                            to: recipient.to_account_info(),
+                        },
+                        &[seeds],
+                    ),
+                    lamports,
+                )?;
+
+                msg!("Sent {} lamports to {}", lamports, to);
+            }
+            Action::SetThreshold { threshold } => {
+                require!(threshold > 0, KeystoreError::InvalidThreshold);
+                require!(
+                    threshold as usize <= identity.keys.len(),
+                    KeystoreError::InvalidThreshold
+                );
+                identity.threshold = threshold;
+                msg!("Threshold set to {}", threshold);
+            }
+            Action::SetSpendLimit { limit, window_secs } => {
+                require!(
+                    limit == 0 || window_secs > 0,
+                    KeystoreError::InvalidArgument
+                );
+                identity.spend_limit = limit;
+                identity.window_secs = window_secs;
+                identity.window_start = Clock::get()?.unix_timestamp;
+                identity.spent_in_window = 0;
+                msg!("Spend limit set to {} lamports per {}s", limit, window_secs);
+            }
+            Action::SendCrossChain { .. } => {
+                // Needs the Wormhole bridge accounts carried by
+                // `execute_cross_chain`, which this context doesn't have.
+                return Err(KeystoreError::CrossChainRequiresDedicatedInstruction.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Handler for WebAuthn signatures
 /// 
 /// WebAuthn signs: authenticatorData || SHA256(clientDataJSON)
 /// The clientDataJSON contains a "challenge" field which is base64url(SHA256(our_message))
 pub fn handler_webauthn(
     ctx: Context<Execute>,
-    action: Action,
+    actions: Vec<Action>,
     webauthn_sig: WebAuthnSignatureData,
+    expires_at: i64,
 ) -> Result<()> {
-    let identity = &mut ctx.accounts.identity;
-    
-    // Build expected message (action + nonce)
-    let expected_message = build_message(&action, identity.nonce)?;
+    let identity_key = ctx.accounts.identity.key();
+
+    require!(
+        !actions.is_empty() && actions.len() <= MAX_ACTIONS,
+        KeystoreError::TooManyActions
+    );
+
+    // Reject signatures over a message that has already expired
+    require!(
+        expires_at > Clock::get()?.unix_timestamp,
+        KeystoreError::MessageExpired
+    );
+
+    // Build expected message (domain tag + program id + identity + cluster + nonce + expiry + actions)
+    let expected_message = build_message(&actions, &identity_key, ctx.accounts.identity.nonce, expires_at)?;
     let expected_challenge = hash(&expected_message);
-    
+
     // Verify the challenge in clientDataJSON matches our expected message
-    verify_webauthn_challenge(&webauthn_sig.client_data_json, expected_challenge.as_ref())?;
-    
+    verify_webauthn_challenge(
+        &webauthn_sig.client_data_json,
+        expected_challenge.as_ref(),
+        &ctx.accounts.identity.allowed_origin_hash,
+    )?;
+
     // Get the key for this signature
-    let key = identity.keys
+    let key = ctx.accounts.identity.keys
         .get(webauthn_sig.key_index as usize)
         .ok_or(KeystoreError::InvalidKeyIndex)?;
-    
+
+    // WebAuthn assertions only come from secp256r1 passkeys
+    require!(
+        key.key_type == KeyType::Secp256r1,
+        KeystoreError::UnsupportedKeyType
+    );
+
+    let key_pubkey = key.pubkey;
+    let last_sign_count = key.last_sign_count;
+
+    // Require user presence and pull the authenticator's clone-detection counter
+    let sign_count = parse_authenticator_data(&webauthn_sig.authenticator_data)?;
+    require!(
+        sign_count > last_sign_count || (sign_count == 0 && last_sign_count == 0),
+        KeystoreError::SignCountReplayed
+    );
+
     // Build the actual signed message: authenticatorData || SHA256(clientDataJSON)
     let client_data_hash = hash(&webauthn_sig.client_data_json);
     let mut signed_message = webauthn_sig.authenticator_data.clone();
     signed_message.extend_from_slice(client_data_hash.as_ref());
-    
+
     // Verify signature via secp256r1 precompile introspection
     secp256r1::verify_secp256r1_signature(
         &ctx.accounts.instructions,
-        &key.pubkey,
+        &key_pubkey,
         &signed_message,
         &webauthn_sig.signature,
     )?;
-    
-    // Increment nonce (before execution to prevent reentrancy)
-    identity.nonce += 1;
-    
-    // Execute action (same as regular handler)
-    match action {
-        Action::Send { to, lamports } => {
-            let recipient = ctx.accounts.recipient
-                .as_ref()
-                .ok_or(KeystoreError::InvalidAccountData)?;
-            
-            if recipient.key() != to {
-                return Err(KeystoreError::InvalidAccountData.into());
-            }
-            
-            let vault_balance = ctx.accounts.vault.lamports();
-            require!(
-                vault_balance >= lamports,
-                KeystoreError::InsufficientFunds
-            );
-            
-            let rent = Rent::get()?;
-            let min_balance = rent.minimum_balance(0);
-            require!(
-                vault_balance.saturating_sub(lamports) >= min_balance || lamports == vault_balance,
-                KeystoreError::InsufficientFunds
-            );
-            
-            let identity_key = identity.key();
-            let seeds: &[&[u8]] = &[
-                b"vault",
-                identity_key.as_ref(),
-                &[identity.vault_bump],
-            ];
-            
-            system_program::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.system_program.to_account_info(),
-                    system_program::Transfer {
-                        from: ctx.accounts.vault.to_account_info(),
-                        to: recipient.to_account_info(),
-                    },
-                    &[seeds],
-                ),
-                lamports,
-            )?;
-            
-            msg!("Sent {} lamports to {}", lamports, to);
-        }
-        Action::SetThreshold { threshold } => {
-            require!(threshold > 0, KeystoreError::InvalidThreshold);
-            require!(
-                threshold as usize <= identity.keys.len(),
-                KeystoreError::InvalidThreshold
-            );
-            identity.threshold = threshold;
-            msg!("Threshold set to {}", threshold);
-        }
-    }
-    
-    Ok(())
+
+    // Persist the new counter so a future replay of this same assertion is rejected
+    ctx.accounts.identity.keys[webauthn_sig.key_index as usize].last_sign_count = sign_count;
+
+    // Increment nonce exactly once for the whole batch (before execution to
+    // prevent reentrancy), then execute every action atomically.
+    ctx.accounts.identity.nonce += 1;
+
+    execute_actions(
+        &mut ctx.accounts.identity,
+        &ctx.accounts.vault,
+        &ctx.accounts.system_program,
+        ctx.remaining_accounts,
+        actions,
+    )
 }
 
-/// Verify that the challenge in clientDataJSON matches our expected hash
-fn verify_webauthn_challenge(client_data_json: &[u8], expected_hash: &[u8]) -> Result<()> {
-    // Parse clientDataJSON to extract challenge
-    // clientDataJSON is like: {"type":"webauthn.get","challenge":"base64url_encoded_challenge",...}
-    
-    let json_str = std::str::from_utf8(client_data_json)
-        .map_err(|_| KeystoreError::InvalidWebAuthnData)?;
-    
-    // Find challenge field - simple parsing without full JSON parser
-    let challenge_prefix = "\"challenge\":\"";
-    let start = json_str.find(challenge_prefix)
+/// Parse the fixed WebAuthn authenticatorData layout far enough to enforce
+/// user presence and extract the clone-detection signature counter.
+///
+/// Layout: rpIdHash (32 bytes) || flags (1 byte) || signCount (4 bytes, BE).
+/// UP (user presence, 0x01) must be set; UV (user verification, 0x04) is
+/// left to a future per-identity policy rather than enforced unconditionally.
+fn parse_authenticator_data(authenticator_data: &[u8]) -> Result<u32> {
+    require!(
+        authenticator_data.len() >= 37,
+        KeystoreError::InvalidAuthenticatorData
+    );
+
+    let flags = authenticator_data[32];
+    require!(flags & 0x01 != 0, KeystoreError::UserPresenceRequired);
+
+    let sign_count = u32::from_be_bytes([
+        authenticator_data[33],
+        authenticator_data[34],
+        authenticator_data[35],
+        authenticator_data[36],
+    ]);
+    Ok(sign_count)
+}
+
+/// Extract a `"field":"value"` string from a flat JSON object without a full
+/// parser. clientDataJSON has no nested objects/escaping in the fields we
+/// care about, so this is sufficient, but it's brittle by construction -
+/// do not reuse it for JSON with escaped quotes.
+fn extract_json_string_field<'a>(json_str: &'a str, field: &str) -> Result<&'a str> {
+    let prefix = format!("\"{}\":\"", field);
+    let start = json_str.find(prefix.as_str())
         .ok_or(KeystoreError::InvalidWebAuthnData)?;
-    let start = start + challenge_prefix.len();
+    let start = start + prefix.len();
     let end = json_str[start..].find('"')
         .ok_or(KeystoreError::InvalidWebAuthnData)?;
-    let challenge_b64 = &json_str[start..start+end];
-    
-    // Decode base64url
-    let challenge = base64url_decode(challenge_b64)
+    Ok(&json_str[start..start + end])
+}
+
+/// Verify clientDataJSON in full: it must be a `webauthn.get` assertion, its
+/// `origin` must hash to the identity's `allowed_origin_hash`, and its
+/// `challenge` must decode to `expected_hash`.
+fn verify_webauthn_challenge(
+    client_data_json: &[u8],
+    expected_hash: &[u8],
+    allowed_origin_hash: &[u8; 32],
+) -> Result<()> {
+    // clientDataJSON is like:
+    // {"type":"webauthn.get","challenge":"...","origin":"https://app.example.com",...}
+    let json_str = std::str::from_utf8(client_data_json)
         .map_err(|_| KeystoreError::InvalidWebAuthnData)?;
-    
-    // Compare with expected hash
-    if challenge.as_slice() != expected_hash {
-        msg!("Challenge mismatch!");
-        msg!("Expected: {:?}", expected_hash);
-        msg!("Got: {:?}", challenge.as_slice());
-        return Err(KeystoreError::InvalidWebAuthnData.into());
-    }
-    
+
+    // Reject assertions signed for a different ceremony (e.g. registration).
+    let cd_type = extract_json_string_field(json_str, "type")?;
+    require!(cd_type == "webauthn.get", KeystoreError::InvalidWebAuthnData);
+
+    // Bind the assertion to the origin the identity was created for, so a
+    // signature minted on an attacker's phishing origin cannot be replayed
+    // against this program even though the challenge matches.
+    let origin = extract_json_string_field(json_str, "origin")?;
+    let origin_hash = hash(origin.as_bytes());
+    require!(
+        origin_hash.as_ref() == allowed_origin_hash.as_slice(),
+        KeystoreError::OriginMismatch
+    );
+
+    let challenge_b64 = extract_json_string_field(json_str, "challenge")?;
+    let challenge = base64url_decode(challenge_b64)?;
+
+    require!(challenge.as_slice() == expected_hash, KeystoreError::InvalidWebAuthnData);
+
     msg!("WebAuthn challenge verified!");
     Ok(())
 }
 
-/// Decode base64url (no padding variant used by WebAuthn)
-fn base64url_decode(input: &str) -> std::result::Result<Vec<u8>, ()> {
-    // Base64url alphabet: A-Z a-z 0-9 - _
+/// Decode base64url (unpadded, as used by WebAuthn), rejecting malformed
+/// input rather than silently skipping it: invalid characters, a length
+/// congruent to 1 mod 4, and leftover trailing bits that aren't all zero
+/// are all errors.
+fn base64url_decode(input: &str) -> Result<Vec<u8>> {
+    require!(input.len() % 4 != 1, KeystoreError::InvalidWebAuthnData);
+
     let mut output = Vec::with_capacity(input.len() * 3 / 4);
     let mut buffer: u32 = 0;
-    let mut bits_collected = 0;
-    
+    let mut bits_collected: u32 = 0;
+
     for c in input.chars() {
         let val = match c {
             'A'..='Z' => c as u32 - 'A' as u32,
@@ -292,20 +440,85 @@ fn base64url_decode(input: &str) -> std::result::Result<Vec<u8>, ()> {
             '0'..='9' => c as u32 - '0' as u32 + 52,
             '-' => 62,
             '_' => 63,
-            '=' => continue, // padding
-            _ => return Err(()),
+            _ => return Err(KeystoreError::InvalidWebAuthnData.into()),
         };
-        
+
         buffer = (buffer << 6) | val;
         bits_collected += 6;
-        
+
         if bits_collected >= 8 {
             bits_collected -= 8;
             output.push((buffer >> bits_collected) as u8);
             buffer &= (1 << bits_collected) - 1;
         }
     }
-    
+
+    // Any leftover bits must be padding zero bits, not encoded data.
+    require!(buffer == 0, KeystoreError::InvalidWebAuthnData);
+
     Ok(output)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authenticator_data(flags: u8, sign_count: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 37];
+        data[32] = flags;
+        data[33..37].copy_from_slice(&sign_count.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_authenticator_data_extracts_sign_count() {
+        let data = authenticator_data(0x01, 42);
+        assert_eq!(parse_authenticator_data(&data).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_parse_authenticator_data_rejects_missing_user_presence() {
+        let data = authenticator_data(0x00, 1);
+        assert!(parse_authenticator_data(&data).is_err());
+    }
+
+    #[test]
+    fn test_parse_authenticator_data_rejects_truncated_input() {
+        let data = authenticator_data(0x01, 1);
+        assert!(parse_authenticator_data(&data[..36]).is_err());
+    }
+
+    #[test]
+    fn test_base64url_decode_round_trips() {
+        // "hello" base64url-encoded (no padding).
+        assert_eq!(base64url_decode("aGVsbG8").unwrap(), b"hello".to_vec());
+    }
+
+    #[test]
+    fn test_base64url_decode_rejects_invalid_alphabet() {
+        // Length 8 passes the length check, so '!' is what gets rejected.
+        assert!(base64url_decode("not!vali").is_err());
+    }
+
+    #[test]
+    fn test_base64url_decode_rejects_length_congruent_to_one_mod_four() {
+        assert!(base64url_decode("a").is_err());
+    }
+
+    #[test]
+    fn test_base64url_decode_rejects_nonzero_trailing_bits() {
+        // Valid alphabet and length, but the leftover bits after the last
+        // full byte aren't zero padding - decoding it would silently drop data.
+        assert!(base64url_decode("AB").is_err());
+    }
+
+    #[test]
+    fn test_extract_json_string_field_and_origin_binding() {
+        let client_data = br#"{"type":"webauthn.get","challenge":"aGVsbG8","origin":"https://example.com"}"#;
+        let json_str = std::str::from_utf8(client_data).unwrap();
+        assert_eq!(extract_json_string_field(json_str, "type").unwrap(), "webauthn.get");
+        assert_eq!(extract_json_string_field(json_str, "origin").unwrap(), "https://example.com");
+        assert!(extract_json_string_field(json_str, "missing").is_err());
+    }
+}
+
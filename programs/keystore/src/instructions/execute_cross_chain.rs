@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
+use anchor_lang::system_program;
+use crate::state::*;
+use crate::error::KeystoreError;
+use crate::{Action, SignatureData};
+use crate::instructions::execute::{build_message, verify_signatures};
+use std::collections::HashSet;
+
+/// Wormhole's chain id for Solana, used as the `source_chain` field of the
+/// payload we post to the core bridge.
+const SOLANA_WORMHOLE_CHAIN_ID: u16 = 1;
+
+/// "Finalized" consistency level, i.e. wait for the block to be rooted
+/// before guardians sign a VAA for this message.
+const CONSISTENCY_LEVEL_FINALIZED: u8 = 1;
+
+/// Byte offset of the `u64` message fee within the Wormhole `BridgeData`
+/// account (after the 8-byte Anchor/Borsh account discriminant, a u32
+/// guardian_set_index and a u64 last_lamports field).
+const WORMHOLE_CONFIG_FEE_OFFSET: usize = 8 + 4 + 8;
+
+// IMPORTANT: this must match the Wormhole core bridge program deployed to
+// the cluster this program targets (mainnet-beta, devnet, etc) - update
+// alongside `declare_id!` in lib.rs when retargeting a deployment.
+anchor_lang::solana_program::declare_id!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+
+#[derive(Accounts)]
+pub struct ExecuteCrossChain<'info> {
+    #[account(mut)]
+    pub identity: Account<'info, Identity>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", identity.key().as_ref()],
+        bump = identity.vault_bump,
+    )]
+    /// CHECK: PDA vault; pays the Wormhole message fee and signs the CPI as the message emitter
+    pub vault: SystemAccount<'info>,
+
+    /// Covers rent for the freshly created Wormhole message account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Instructions sysvar for verifying the secp256r1/ed25519 precompile
+    #[account(address = ix_sysvar::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    /// CHECK: Wormhole core bridge program
+    #[account(address = ID)]
+    pub wormhole_program: AccountInfo<'info>,
+
+    /// CHECK: Wormhole `BridgeData` config account (holds the guardian set and message fee)
+    #[account(mut)]
+    pub wormhole_config: AccountInfo<'info>,
+
+    /// CHECK: Wormhole fee collector the message fee is paid into
+    #[account(mut)]
+    pub wormhole_fee_collector: AccountInfo<'info>,
+
+    /// CHECK: fresh account the core bridge initializes to hold this message; must sign as it's created in this instruction
+    #[account(mut)]
+    pub wormhole_message: Signer<'info>,
+
+    /// CHECK: Wormhole per-emitter sequence-tracking PDA, owned by the core bridge
+    #[account(mut)]
+    pub wormhole_sequence: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Authorize and relay an `Action::SendCrossChain` to another chain by
+/// posting a Wormhole message from the identity's vault. Verification mirrors
+/// `execute::handler` exactly (same signed-message format, nonce, and
+/// expiry), but the payout happens via a bridge CPI instead of a local
+/// `system_program::transfer`.
+pub fn handler(
+    ctx: Context<ExecuteCrossChain>,
+    target_chain: u16,
+    target_address: [u8; 32],
+    amount: u64,
+    batch_nonce: u32,
+    sigs: Vec<SignatureData>,
+    expires_at: i64,
+) -> Result<()> {
+    let identity_key = ctx.accounts.identity.key();
+    let action = Action::SendCrossChain {
+        target_chain,
+        target_address,
+        amount,
+        nonce: batch_nonce,
+    };
+
+    require!(!sigs.is_empty(), KeystoreError::ThresholdNotMet);
+    require!(
+        sigs.len() >= ctx.accounts.identity.threshold as usize,
+        KeystoreError::ThresholdNotMet
+    );
+
+    let mut used_keys = HashSet::new();
+    for sig in &sigs {
+        require!(
+            used_keys.insert(sig.key_index),
+            KeystoreError::SignatureVerificationFailed
+        );
+    }
+
+    require!(
+        expires_at > Clock::get()?.unix_timestamp,
+        KeystoreError::MessageExpired
+    );
+
+    let message = build_message(
+        &vec![action],
+        &identity_key,
+        ctx.accounts.identity.nonce,
+        expires_at,
+    )?;
+
+    verify_signatures(
+        &ctx.accounts.instructions,
+        &ctx.accounts.identity.keys,
+        &sigs,
+        &message,
+    )?;
+
+    // Increment nonce before the CPI to prevent reentrancy/replay
+    ctx.accounts.identity.nonce += 1;
+
+    let vault_bump = ctx.accounts.identity.vault_bump;
+    let seeds: &[&[u8]] = &[b"vault", identity_key.as_ref(), &[vault_bump]];
+
+    // Pay the Wormhole message fee from the vault, exactly like a local Send
+    let message_fee = read_wormhole_message_fee(&ctx.accounts.wormhole_config)?;
+    if message_fee > 0 {
+        require!(
+            ctx.accounts.vault.lamports() >= message_fee,
+            KeystoreError::InsufficientFunds
+        );
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.wormhole_fee_collector.to_account_info(),
+                },
+                &[seeds],
+            ),
+            message_fee,
+        )?;
+    }
+
+    let payload = encode_cross_chain_payload(target_chain, &target_address, amount, batch_nonce);
+    post_wormhole_message(&ctx, payload, batch_nonce, &[seeds])?;
+
+    msg!(
+        "Cross-chain send queued: {} lamports to chain {} address {:?}",
+        amount,
+        target_chain,
+        target_address
+    );
+    Ok(())
+}
+
+/// Encode the VAA payload: source/target chain ids, target address, amount,
+/// and batch nonce, all big-endian, so it can be consumed by the standard
+/// token-bridge VAA flow on the destination chain.
+fn encode_cross_chain_payload(target_chain: u16, target_address: &[u8; 32], amount: u64, batch_nonce: u32) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(2 + 2 + 32 + 8 + 4);
+    payload.extend_from_slice(&SOLANA_WORMHOLE_CHAIN_ID.to_be_bytes());
+    payload.extend_from_slice(&target_chain.to_be_bytes());
+    payload.extend_from_slice(target_address);
+    payload.extend_from_slice(&amount.to_be_bytes());
+    payload.extend_from_slice(&batch_nonce.to_be_bytes());
+    payload
+}
+
+/// Read the `u64` message fee out of the Wormhole `BridgeData` account.
+fn read_wormhole_message_fee(wormhole_config: &AccountInfo) -> Result<u64> {
+    let data = wormhole_config.try_borrow_data()?;
+    require!(
+        data.len() >= WORMHOLE_CONFIG_FEE_OFFSET + 8,
+        KeystoreError::InvalidAccountData
+    );
+    let mut fee_bytes = [0u8; 8];
+    fee_bytes.copy_from_slice(&data[WORMHOLE_CONFIG_FEE_OFFSET..WORMHOLE_CONFIG_FEE_OFFSET + 8]);
+    Ok(u64::from_le_bytes(fee_bytes))
+}
+
+/// CPI into the Wormhole core bridge's `post_message` instruction, signed by
+/// the vault PDA itself acting as the message emitter - the same account
+/// `signer_seeds` was derived for, so the two can never drift apart.
+fn post_wormhole_message(
+    ctx: &Context<ExecuteCrossChain>,
+    payload: Vec<u8>,
+    batch_nonce: u32,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = vec![1u8]; // post_message instruction discriminant
+    data.extend_from_slice(&batch_nonce.to_le_bytes());
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(&payload);
+    data.push(CONSISTENCY_LEVEL_FINALIZED);
+
+    let accounts = vec![
+        AccountMeta::new(ctx.accounts.wormhole_config.key(), false),
+        AccountMeta::new(ctx.accounts.wormhole_message.key(), true),
+        AccountMeta::new_readonly(ctx.accounts.vault.key(), true),
+        AccountMeta::new(ctx.accounts.wormhole_sequence.key(), false),
+        AccountMeta::new(ctx.accounts.payer.key(), true),
+        AccountMeta::new(ctx.accounts.wormhole_fee_collector.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.clock.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.rent.key(), false),
+        AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+    ];
+
+    let ix = Instruction {
+        program_id: ctx.accounts.wormhole_program.key(),
+        accounts,
+        data,
+    };
+
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.wormhole_config.to_account_info(),
+            ctx.accounts.wormhole_message.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.wormhole_sequence.to_account_info(),
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.wormhole_fee_collector.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
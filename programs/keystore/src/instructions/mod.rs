@@ -0,0 +1,11 @@
+pub mod add_key;
+pub mod create;
+pub mod execute;
+pub mod execute_cross_chain;
+pub mod register_credential;
+
+pub use add_key::*;
+pub use create::*;
+pub use execute::*;
+pub use execute_cross_chain::*;
+pub use register_credential::*;
@@ -1,19 +1,20 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::error::KeystoreError;
+use crate::attestation;
 
 #[derive(Accounts)]
 pub struct RegisterCredential<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
-    
+
     #[account(
         mut,
         seeds = [b"identity", authority.key().as_ref()],
         bump = identity.bump,
     )]
     pub identity: Account<'info, Identity>,
-    
+
     #[account(
         init,
         payer = authority,
@@ -22,44 +23,59 @@ pub struct RegisterCredential<'info> {
         bump,
     )]
     pub credential_registry: Account<'info, CredentialRegistry>,
-    
+
     pub system_program: Program<'info, System>,
 }
 
+/// Derive the passkey's pubkey and credentialId from its WebAuthn attestation
+/// and register both atomically, so the signing key is provably the one the
+/// authenticator attested to rather than a value handed over separately.
 pub fn handler(
     ctx: Context<RegisterCredential>,
-    credential_id: Vec<u8>,
+    attestation_object: Vec<u8>,
     device_name: String,
 ) -> Result<()> {
     let credential_registry = &mut ctx.accounts.credential_registry;
-    let identity = &ctx.accounts.identity;
-    
-    // Validate inputs
+    let identity = &mut ctx.accounts.identity;
+
     require!(
-        credential_id.len() <= 256,
+        device_name.len() <= 32 && !device_name.is_empty(),
         KeystoreError::InvalidArgument
     );
-    
+
     require!(
-        !credential_id.is_empty(),
-        KeystoreError::InvalidArgument
+        identity.keys.len() < Identity::MAX_KEYS,
+        KeystoreError::MaxKeysReached
     );
-    
+
+    let (pubkey, credential_id) = attestation::parse_attestation_object(&attestation_object)?;
+
     require!(
-        device_name.len() <= 32 && !device_name.is_empty(),
+        !credential_id.is_empty() && credential_id.len() <= CredentialRegistry::MAX_CREDENTIAL_ID_LEN,
         KeystoreError::InvalidArgument
     );
-    
-    // Get the key index (last key added)
-    let key_index = (identity.keys.len() - 1) as u8;
-    
+
+    for key in &identity.keys {
+        require!(key.pubkey != pubkey, KeystoreError::DuplicateKey);
+    }
+
+    let clock = Clock::get()?;
+    let key_index = identity.keys.len() as u8;
+    identity.keys.push(RegisteredKey {
+        key_type: KeyType::Secp256r1,
+        pubkey,
+        name: device_name.clone(),
+        added_at: clock.unix_timestamp,
+        last_sign_count: 0,
+    });
+
     credential_registry.bump = ctx.bumps.credential_registry;
     credential_registry.identity = identity.key();
     credential_registry.key_index = key_index;
     credential_registry.credential_id = credential_id;
     credential_registry.device_name = device_name;
-    credential_registry.registered_at = Clock::get()?.unix_timestamp;
-    
+    credential_registry.registered_at = clock.unix_timestamp;
+
     msg!("Credential registered for key index {}", key_index);
     Ok(())
 }
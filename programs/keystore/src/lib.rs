@@ -4,8 +4,11 @@ pub mod state;
 pub mod error;
 pub mod instructions;
 pub mod secp256r1;
+pub mod ed25519;
+pub mod attestation;
 
 use instructions::*;
+use state::KeyType;
 
 // IMPORTANT: After deployment, update this ID in THREE places:
 // 1. This file (declare_id! below)
@@ -20,42 +23,74 @@ pub mod keystore {
     pub fn create_identity(
         ctx: Context<CreateIdentity>,
         pubkey: [u8; 33],
+        key_type: KeyType,
         device_name: String,
+        allowed_origin_hash: [u8; 32],
     ) -> Result<()> {
-        instructions::create::handler(ctx, pubkey, device_name)
+        instructions::create::handler(ctx, pubkey, key_type, device_name, allowed_origin_hash)
     }
 
     pub fn add_key(
         ctx: Context<AddKey>,
         new_pubkey: [u8; 33],
+        key_type: KeyType,
         device_name: String,
     ) -> Result<()> {
-        instructions::add_key::handler(ctx, new_pubkey, device_name)
+        instructions::add_key::handler(ctx, new_pubkey, key_type, device_name)
     }
 
+    /// Execute a batch of up to `instructions::execute::MAX_ACTIONS` actions
+    /// atomically under a single signature set.
     pub fn execute(
         ctx: Context<Execute>,
-        action: Action,
+        actions: Vec<Action>,
         sigs: Vec<SignatureData>,
+        expires_at: i64,
     ) -> Result<()> {
-        instructions::execute::handler(ctx, action, sigs)
+        instructions::execute::handler(ctx, actions, sigs, expires_at)
     }
 
-    /// Execute with WebAuthn signature format
+    /// Execute a batch of actions with WebAuthn signature format
     pub fn execute_webauthn(
         ctx: Context<Execute>,
-        action: Action,
+        actions: Vec<Action>,
         webauthn_sig: WebAuthnSignatureData,
+        expires_at: i64,
     ) -> Result<()> {
-        instructions::execute::handler_webauthn(ctx, action, webauthn_sig)
+        instructions::execute::handler_webauthn(ctx, actions, webauthn_sig, expires_at)
     }
 
+    /// Authorize a `SendCrossChain` action and relay it to another chain by
+    /// posting a Wormhole message from the identity's vault.
+    pub fn execute_cross_chain(
+        ctx: Context<ExecuteCrossChain>,
+        target_chain: u16,
+        target_address: [u8; 32],
+        amount: u64,
+        batch_nonce: u32,
+        sigs: Vec<SignatureData>,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::execute_cross_chain::handler(
+            ctx,
+            target_chain,
+            target_address,
+            amount,
+            batch_nonce,
+            sigs,
+            expires_at,
+        )
+    }
+
+    /// Register a passkey from its WebAuthn attestation: derives the
+    /// compressed pubkey and credentialId directly from `attestation_object`
+    /// rather than trusting a pubkey supplied separately by the frontend.
     pub fn register_credential(
         ctx: Context<RegisterCredential>,
-        credential_id: Vec<u8>,
+        attestation_object: Vec<u8>,
         device_name: String,
     ) -> Result<()> {
-        instructions::register_credential::handler(ctx, credential_id, device_name)
+        instructions::register_credential::handler(ctx, attestation_object, device_name)
     }
 }
 
@@ -63,6 +98,18 @@ pub mod keystore {
 pub enum Action {
     Send { to: Pubkey, lamports: u64 },
     SetThreshold { threshold: u8 },
+    /// Set the rolling spend-limit policy enforced on `Send`. A `limit` of
+    /// 0 means unlimited.
+    SetSpendLimit { limit: u64, window_secs: i64 },
+    /// Authorize a transfer to another chain via the Wormhole core bridge.
+    /// Only valid through `execute_cross_chain`, which carries the bridge
+    /// accounts this action needs; batching it through `execute` fails.
+    SendCrossChain {
+        target_chain: u16,
+        target_address: [u8; 32],
+        amount: u64,
+        nonce: u32,
+    },
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
@@ -5,28 +5,31 @@ use crate::error::KeystoreError;
 pub const SECP256R1_PROGRAM_ID: Pubkey = pubkey!("Secp256r1SigVerify1111111111111111111111111");
 
 /// secp256r1 instruction data format (SIMD-0075):
-/// 
+///
 /// Header (2 bytes):
-///   - u8: number of signatures (we expect 1)
+///   - u8: number of signatures
 ///   - u8: padding
 ///
-/// Per-signature offsets (14 bytes - all u16):
+/// Per-signature offsets (14 bytes - all u16), repeated `num_signatures` times:
 ///   - u16: signature_offset
 ///   - u16: signature_instruction_index (0xFFFF = current instruction)
-///   - u16: public_key_offset  
+///   - u16: public_key_offset
 ///   - u16: public_key_instruction_index (0xFFFF = current instruction)
 ///   - u16: message_data_offset
 ///   - u16: message_data_size
 ///   - u16: message_instruction_index (0xFFFF = current instruction)
 ///
-/// Data section (following header + offsets):
+/// Data section (following header + offsets), one chunk per signature; each
+/// field is located through its offset, and the builders below write every
+/// chunk as signature, then pubkey, then message:
 ///   - pubkey: 33 bytes (compressed secp256r1)
 ///   - signature: 64 bytes (r || s)
 ///   - message: variable length
 
-#[derive(Debug)]
-pub struct Secp256r1InstructionData {
-    pub num_signatures: u8,
+/// Offsets for a single signature within a (possibly multi-signature)
+/// secp256r1 precompile instruction.
+#[derive(Debug, Clone, Copy)]
+pub struct Secp256r1SignatureOffsets {
     pub signature_offset: u16,
     pub signature_ix_index: u16,
     pub pubkey_offset: u16,
@@ -36,149 +39,248 @@ pub struct Secp256r1InstructionData {
     pub message_ix_index: u16,
 }
 
-impl Secp256r1InstructionData {
-    /// Parse secp256r1 instruction data
-    /// 
-    /// Format: 2-byte header + 14-byte offsets struct
-    /// Header: [num_signatures: u8, padding: u8]
-    /// Offsets: 7 x u16 = 14 bytes
-    pub fn try_from_slice(data: &[u8]) -> Result<Self> {
-        msg!("Parsing secp256r1 instruction, data len: {}", data.len());
-        
-        // Log first 20 bytes for debugging
-        if data.len() >= 16 {
-            msg!("First 16 bytes: {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x} {:02x}",
-                data[0], data[1], data[2], data[3], data[4], data[5], data[6], data[7],
-                data[8], data[9], data[10], data[11], data[12], data[13], data[14], data[15]);
-        }
-        
-        // Minimum size: 2 (header) + 14 (offsets) = 16 bytes
-        if data.len() < 16 {
-            msg!("Data too short: {} < 16", data.len());
-            return Err(KeystoreError::InvalidSecp256r1Instruction.into());
-        }
-        
-        let num_signatures = data[0];
-        msg!("num_signatures: {}", num_signatures);
-        if num_signatures != 1 {
-            msg!("Expected 1 signature, got {}", num_signatures);
-            return Err(KeystoreError::InvalidSecp256r1Instruction.into());
+impl Secp256r1SignatureOffsets {
+    const ENCODED_SIZE: usize = 14;
+
+    fn parse(data: &[u8]) -> Self {
+        let signature_offset = u16::from_le_bytes([data[0], data[1]]);
+        let signature_ix_index = u16::from_le_bytes([data[2], data[3]]);
+        let pubkey_offset = u16::from_le_bytes([data[4], data[5]]);
+        let pubkey_ix_index = u16::from_le_bytes([data[6], data[7]]);
+        let message_offset = u16::from_le_bytes([data[8], data[9]]);
+        let message_size = u16::from_le_bytes([data[10], data[11]]);
+        let message_ix_index = u16::from_le_bytes([data[12], data[13]]);
+
+        Self {
+            signature_offset,
+            signature_ix_index,
+            pubkey_offset,
+            pubkey_ix_index,
+            message_offset,
+            message_size,
+            message_ix_index,
         }
-        
-        // Parse offsets (all u16, little-endian)
-        // Offset 0: num_signatures (u8)
-        // Offset 1: padding (u8)
-        // Offset 2-3: signature_offset
-        // Offset 4-5: signature_instruction_index
-        // Offset 6-7: public_key_offset
-        // Offset 8-9: public_key_instruction_index
-        // Offset 10-11: message_data_offset
-        // Offset 12-13: message_data_size
-        // Offset 14-15: message_instruction_index
-        
-        let sig_offset = u16::from_le_bytes([data[2], data[3]]);
-        let sig_ix = u16::from_le_bytes([data[4], data[5]]);
-        let pk_offset = u16::from_le_bytes([data[6], data[7]]);
-        let pk_ix = u16::from_le_bytes([data[8], data[9]]);
-        let msg_offset = u16::from_le_bytes([data[10], data[11]]);
-        let msg_size = u16::from_le_bytes([data[12], data[13]]);
-        let msg_ix = u16::from_le_bytes([data[14], data[15]]);
-        
-        msg!("Parsed offsets: sig_offset={}, sig_ix={:#06x}, pk_offset={}, pk_ix={:#06x}, msg_offset={}, msg_size={}, msg_ix={:#06x}",
-            sig_offset, sig_ix, pk_offset, pk_ix, msg_offset, msg_size, msg_ix);
-        
-        Ok(Self {
-            num_signatures,
-            signature_offset: sig_offset,
-            signature_ix_index: sig_ix,
-            pubkey_offset: pk_offset,
-            pubkey_ix_index: pk_ix,
-            message_offset: msg_offset,
-            message_size: msg_size,
-            message_ix_index: msg_ix,
-        })
     }
-    
+
     /// Extract signature from instruction data or referenced instruction
-    pub fn extract_signature<'a>(
+    pub fn extract_signature(
         &self,
-        instruction_data: &'a [u8],
+        instruction_data: &[u8],
         instructions_sysvar: &AccountInfo,
-    ) -> Result<&'a [u8]> {
-        msg!("Extracting signature: ix_index={:#06x} (expected 0xFFFF), offset={}", 
-            self.signature_ix_index, self.signature_offset);
-        if self.signature_ix_index == 0xFFFF {
-            // Signature is in current instruction
-            let start = self.signature_offset as usize;
-            let end = start + 64;
-            require!(
-                instruction_data.len() >= end,
-                KeystoreError::InvalidSecp256r1Instruction
-            );
-            msg!("Signature extracted from offset {}", start);
-            Ok(&instruction_data[start..end])
-        } else {
-            // Signature is in another instruction (not implemented for simplicity)
-            msg!("Cross-instruction signature references not yet supported");
-            Err(KeystoreError::InvalidSecp256r1Instruction.into())
-        }
+    ) -> Result<Vec<u8>> {
+        let data = resolve_instruction_data(
+            self.signature_ix_index,
+            instruction_data,
+            instructions_sysvar,
+        )?;
+        let start = self.signature_offset as usize;
+        let end = start
+            .checked_add(64)
+            .ok_or(KeystoreError::InvalidDataOffsets)?;
+        require!(data.len() >= end, KeystoreError::InvalidDataOffsets);
+        Ok(data[start..end].to_vec())
     }
-    
+
     /// Extract public key from instruction data or referenced instruction
-    pub fn extract_pubkey<'a>(
+    pub fn extract_pubkey(
         &self,
-        instruction_data: &'a [u8],
+        instruction_data: &[u8],
         instructions_sysvar: &AccountInfo,
-    ) -> Result<&'a [u8]> {
-        msg!("Extracting pubkey: ix_index={:#06x} (expected 0xFFFF), offset={}", 
-            self.pubkey_ix_index, self.pubkey_offset);
-        if self.pubkey_ix_index == 0xFFFF {
-            // Pubkey is in current instruction
-            let start = self.pubkey_offset as usize;
-            let end = start + 33; // Compressed secp256r1 key
-            require!(
-                instruction_data.len() >= end,
-                KeystoreError::InvalidSecp256r1Instruction
-            );
-            msg!("Pubkey extracted from offset {}", start);
-            Ok(&instruction_data[start..end])
-        } else {
-            // Pubkey is in another instruction (not implemented for simplicity)
-            msg!("Cross-instruction pubkey references not yet supported");
-            Err(KeystoreError::InvalidSecp256r1Instruction.into())
-        }
+    ) -> Result<Vec<u8>> {
+        let data = resolve_instruction_data(
+            self.pubkey_ix_index,
+            instruction_data,
+            instructions_sysvar,
+        )?;
+        let start = self.pubkey_offset as usize;
+        let end = start
+            .checked_add(33) // Compressed secp256r1 key
+            .ok_or(KeystoreError::InvalidDataOffsets)?;
+        require!(data.len() >= end, KeystoreError::InvalidDataOffsets);
+        Ok(data[start..end].to_vec())
     }
-    
+
     /// Extract message from instruction data or referenced instruction
-    pub fn extract_message<'a>(
+    pub fn extract_message(
         &self,
-        instruction_data: &'a [u8],
+        instruction_data: &[u8],
         instructions_sysvar: &AccountInfo,
-    ) -> Result<&'a [u8]> {
-        msg!("Extracting message: ix_index={:#06x} (expected 0xFFFF), offset={}, size={}", 
-            self.message_ix_index, self.message_offset, self.message_size);
-        if self.message_ix_index == 0xFFFF {
-            // Message is in current instruction
-            let start = self.message_offset as usize;
-            let end = start + self.message_size as usize;
-            require!(
-                instruction_data.len() >= end,
-                KeystoreError::InvalidSecp256r1Instruction
-            );
-            msg!("Message extracted from offset {}, size {}", start, self.message_size);
-            Ok(&instruction_data[start..end])
-        } else {
-            // Message is in another instruction (not implemented for simplicity)
-            msg!("Cross-instruction message references not yet supported");
-            Err(KeystoreError::InvalidSecp256r1Instruction.into())
+    ) -> Result<Vec<u8>> {
+        let data = resolve_instruction_data(
+            self.message_ix_index,
+            instruction_data,
+            instructions_sysvar,
+        )?;
+        let start = self.message_offset as usize;
+        let end = start
+            .checked_add(self.message_size as usize)
+            .ok_or(KeystoreError::InvalidDataOffsets)?;
+        require!(data.len() >= end, KeystoreError::InvalidDataOffsets);
+        Ok(data[start..end].to_vec())
+    }
+}
+
+/// Resolve the byte slice a `*_instruction_index` refers to: `0xFFFF`
+/// means "this instruction", anything else loads that instruction from
+/// the instructions sysvar (Solana precompiles let a signature, pubkey,
+/// or message live in any instruction of the same transaction).
+///
+/// Shared with the ed25519 precompile module, whose offset layout is
+/// identical.
+pub(crate) fn resolve_instruction_data(
+    ix_index: u16,
+    current_instruction_data: &[u8],
+    instructions_sysvar: &AccountInfo,
+) -> Result<Vec<u8>> {
+    use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
+
+    if ix_index == 0xFFFF {
+        Ok(current_instruction_data.to_vec())
+    } else {
+        let ix = ix_sysvar::load_instruction_at_checked(ix_index as usize, instructions_sysvar)
+            .map_err(|_| KeystoreError::InvalidInstructionIndex)?;
+        Ok(ix.data)
+    }
+}
+
+#[derive(Debug)]
+pub struct Secp256r1InstructionData {
+    pub num_signatures: u8,
+    pub offsets: Vec<Secp256r1SignatureOffsets>,
+}
+
+impl Secp256r1InstructionData {
+    /// Parse secp256r1 instruction data
+    ///
+    /// Format: 2-byte header `[num_signatures, padding]` followed by
+    /// `num_signatures` 14-byte offset structs. Everything after the
+    /// offset table is the packed pubkey/signature/message payload.
+    pub fn try_from_slice(data: &[u8]) -> Result<Self> {
+        // Minimum size: 2 (header) + 14 (at least one offsets struct)
+        require!(
+            data.len() >= 2 + Secp256r1SignatureOffsets::ENCODED_SIZE,
+            KeystoreError::InvalidInstructionDataSize
+        );
+
+        let num_signatures = data[0];
+        require!(num_signatures != 0, KeystoreError::InvalidInstructionDataSize);
+
+        let offsets_len = (num_signatures as usize)
+            .checked_mul(Secp256r1SignatureOffsets::ENCODED_SIZE)
+            .ok_or(KeystoreError::InvalidInstructionDataSize)?;
+        let header_len = 2usize
+            .checked_add(offsets_len)
+            .ok_or(KeystoreError::InvalidInstructionDataSize)?;
+        require!(
+            data.len() >= header_len,
+            KeystoreError::InvalidInstructionDataSize
+        );
+
+        let mut offsets = Vec::with_capacity(num_signatures as usize);
+        for i in 0..num_signatures as usize {
+            let start = 2 + i * Secp256r1SignatureOffsets::ENCODED_SIZE;
+            let end = start + Secp256r1SignatureOffsets::ENCODED_SIZE;
+            offsets.push(Secp256r1SignatureOffsets::parse(&data[start..end]));
+        }
+
+        Ok(Self {
+            num_signatures,
+            offsets,
+        })
+    }
+}
+
+/// Find the secp256r1 precompile instruction preceding the current
+/// instruction in this transaction.
+///
+/// Scans backwards from the current instruction and returns the first
+/// instruction whose program id is the secp256r1 precompile. Distinct
+/// from a crypto mismatch: if no such instruction exists at all, an
+/// attacker has simply omitted the precompile and we say so explicitly
+/// instead of falling through to a generic verification failure.
+fn find_secp256r1_instruction(
+    instructions_sysvar: &AccountInfo,
+) -> Result<anchor_lang::solana_program::instruction::Instruction> {
+    use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
+
+    let current_idx = ix_sysvar::load_current_index_checked(instructions_sysvar)
+        .map_err(|_| KeystoreError::InvalidSecp256r1Instruction)?;
+
+    for i in (0..current_idx).rev() {
+        let ix = ix_sysvar::load_instruction_at_checked(i as usize, instructions_sysvar)
+            .map_err(|_| KeystoreError::InvalidSecp256r1Instruction)?;
+
+        if ix.program_id == SECP256R1_PROGRAM_ID {
+            return Ok(ix);
         }
     }
+
+    Err(KeystoreError::MissingSecp256r1Instruction.into())
+}
+
+/// Perform a rigorous structural validation of a secp256r1 precompile
+/// instruction, modeled on the checks Solana's own precompile `verify`
+/// functions run before a signature is ever trusted: the instruction
+/// must be well-formed, and every declared offset, plus its size, must
+/// fit within the referenced instruction's data. This lets callers tell
+/// a malformed transaction (a distinct `KeystoreError` variant per
+/// failure class) apart from a genuine signature/pubkey/message
+/// mismatch.
+pub fn validate_secp256r1_instruction(
+    ix: &anchor_lang::solana_program::instruction::Instruction,
+    instructions_sysvar: &AccountInfo,
+) -> Result<Secp256r1InstructionData> {
+    require!(
+        ix.program_id == SECP256R1_PROGRAM_ID,
+        KeystoreError::InvalidSecp256r1Instruction
+    );
+
+    let parsed = Secp256r1InstructionData::try_from_slice(&ix.data)?;
+
+    for offsets in &parsed.offsets {
+        validate_offset(offsets.signature_ix_index, offsets.signature_offset, 64, &ix.data, instructions_sysvar)?;
+        validate_offset(offsets.pubkey_ix_index, offsets.pubkey_offset, 33, &ix.data, instructions_sysvar)?;
+        validate_offset(
+            offsets.message_ix_index,
+            offsets.message_offset,
+            offsets.message_size as usize,
+            &ix.data,
+            instructions_sysvar,
+        )?;
+    }
+
+    Ok(parsed)
+}
+
+/// Confirm that `offset + size` fits within the instruction `ix_index`
+/// refers to (`0xFFFF` meaning the current instruction).
+fn validate_offset(
+    ix_index: u16,
+    offset: u16,
+    size: usize,
+    current_instruction_data: &[u8],
+    instructions_sysvar: &AccountInfo,
+) -> Result<()> {
+    let data_len = if ix_index == 0xFFFF {
+        current_instruction_data.len()
+    } else {
+        use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
+        let ix = ix_sysvar::load_instruction_at_checked(ix_index as usize, instructions_sysvar)
+            .map_err(|_| KeystoreError::InvalidInstructionIndex)?;
+        ix.data.len()
+    };
+
+    let end = (offset as usize)
+        .checked_add(size)
+        .ok_or(KeystoreError::InvalidDataOffsets)?;
+    require!(data_len >= end, KeystoreError::InvalidDataOffsets);
+    Ok(())
 }
 
 /// Verify a secp256r1 signature using the precompile
-/// 
+///
 /// This function:
-/// 1. Finds the secp256r1 instruction in the transaction
+/// 1. Finds and structurally validates the secp256r1 instruction in the transaction
 /// 2. Parses its data to extract pubkey, signature, and message
 /// 3. Verifies they match what we expect
 /// 4. Trusts that the precompile verified the signature (it fails tx if invalid)
@@ -188,98 +290,70 @@ pub fn verify_secp256r1_signature(
     expected_message: &[u8],
     expected_signature: &[u8; 64],
 ) -> Result<()> {
-    msg!("Verifying secp256r1 signature via precompile");
-    use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
-    
-    // Load current instruction index
-    msg!("Loading current instruction index");
-    let current_idx = ix_sysvar::load_current_index_checked(instructions_sysvar)
-        .map_err(|_| KeystoreError::InvalidSecp256r1Instruction)?;
-    msg!("Current instruction index: {}", current_idx);
+    let ix = find_secp256r1_instruction(instructions_sysvar)?;
+    let parsed = validate_secp256r1_instruction(&ix, instructions_sysvar)?;
 
-    // Look backwards for secp256r1 instruction
-    let mut found_valid = false;
-    
-    for i in (0..current_idx).rev() {
-        msg!("Checking instruction at index {}", i);
-        let ix = ix_sysvar::load_instruction_at_checked(i as usize, instructions_sysvar)
-            .map_err(|_| KeystoreError::InvalidSecp256r1Instruction)?;
-        msg!("instruction loaded, checking");
-        
-        // Check if this is a secp256r1 instruction
-        if ix.program_id != SECP256R1_PROGRAM_ID {
-            continue;
-        }
-        
-        // Check instruction has sufficient data (2-byte header + 14-byte offsets = 16 minimum)
-        if ix.data.len() < 16 {
-            msg!("secp256r1 instruction data too short: {} bytes", ix.data.len());
-            continue;
+    for offsets in &parsed.offsets {
+        let sig = offsets.extract_signature(&ix.data, instructions_sysvar)?;
+        let pk = offsets.extract_pubkey(&ix.data, instructions_sysvar)?;
+        let msg_data = offsets.extract_message(&ix.data, instructions_sysvar)?;
+
+        if pk == expected_pubkey.as_slice()
+            && sig == expected_signature.as_slice()
+            && msg_data == expected_message
+        {
+            // The precompile already verified the crypto for this instruction.
+            return Ok(());
         }
-        
-        // Parse instruction data
-        msg!("Parsing secp256r1 instruction data");
-        match Secp256r1InstructionData::try_from_slice(&ix.data) {
-            Ok(parsed) => {
-                // Extract components
-                if let (Ok(sig), Ok(pk), Ok(msg_data)) = (
-                    parsed.extract_signature(&ix.data, instructions_sysvar),
-                    parsed.extract_pubkey(&ix.data, instructions_sysvar),
-                    parsed.extract_message(&ix.data, instructions_sysvar),
-                ) {
-                    // Debug: Log first few bytes
-                    msg!("Extracted signature length: {}, pubkey length: {}, message length: {}", sig.len(), pk.len(), msg_data.len());
-                    msg!("extracted_signature: {:?}", sig);
-                    msg!("expected_signature: {:?}", expected_signature);
-                    msg!("extracted_pubkey: {:?}", pk);
-                    msg!("expected_pubkey: {:?}", expected_pubkey);
-                    msg!("extracted_message: {:?}", msg_data);
-                    msg!("expected_message: {:?}", expected_message);
-                    
-                    // Verify public key matches
-                    if pk.len() != 33 || pk != expected_pubkey.as_slice() {
-                        msg!("Public key mismatch");
-                        continue;
-                    }
-                    
-                    // Verify signature matches
-                    if sig.len() != 64 || sig != expected_signature.as_slice() {
-                        msg!("Signature mismatch");
-                        continue;
-                    }
-                    
-                    // Verify message matches
-                    if msg_data != expected_message {
-                        msg!("Message mismatch");
-                        continue;
-                    }
-                    
-                    // All checks passed - the precompile verified the crypto
-                    msg!("Found valid matching secp256r1 instruction");
-                    found_valid = true;
-                    break;
-                }
-                else {
-                    msg!("Failed to extract secp256r1 components");
-                    continue;
-                }
-            }
-            Err(e) => {
-                msg!("Failed to parse secp256r1 instruction: {:?}", e);
-                continue;
+    }
+
+    Err(KeystoreError::SignatureVerificationFailed.into())
+}
+
+/// Verify a threshold of signatures packed into a single secp256r1
+/// precompile instruction.
+///
+/// Finds and structurally validates the one secp256r1 instruction
+/// preceding the current instruction, extracts every embedded
+/// `(pubkey, message, signature)` triple, and confirms that at least
+/// `threshold` distinct entries from `expected_triples` are present
+/// among them. Reusing the same expected key twice to satisfy the
+/// threshold is rejected, matching the duplicate-key semantics of
+/// [`SignatureVerificationFailed`](KeystoreError::SignatureVerificationFailed).
+pub fn verify_secp256r1_signatures(
+    instructions_sysvar: &AccountInfo,
+    expected_triples: &[(&[u8; 33], &[u8], &[u8; 64])],
+    threshold: usize,
+) -> Result<()> {
+    let ix = find_secp256r1_instruction(instructions_sysvar)?;
+    let parsed = validate_secp256r1_instruction(&ix, instructions_sysvar)?;
+
+    let mut matched_keys: std::collections::HashSet<[u8; 33]> = std::collections::HashSet::new();
+
+    for offsets in &parsed.offsets {
+        let sig = offsets.extract_signature(&ix.data, instructions_sysvar)?;
+        let pk = offsets.extract_pubkey(&ix.data, instructions_sysvar)?;
+        let msg_data = offsets.extract_message(&ix.data, instructions_sysvar)?;
+
+        for (expected_pubkey, expected_message, expected_signature) in expected_triples {
+            if pk == expected_pubkey.as_slice()
+                && sig == expected_signature.as_slice()
+                && msg_data == *expected_message
+            {
+                matched_keys.insert(**expected_pubkey);
             }
         }
     }
-    
-    if !found_valid {
-        return Err(KeystoreError::SignatureVerificationFailed.into());
-    }
-    
+
+    require!(
+        matched_keys.len() >= threshold,
+        KeystoreError::SignatureVerificationFailed
+    );
     Ok(())
 }
 
-/// Build a secp256r1 verification instruction
-/// 
+/// Build a secp256r1 verification instruction carrying a single signature.
+///
 /// This is a helper for clients to build the verification instruction
 /// that must precede the execute instruction.
 pub fn build_secp256r1_instruction(
@@ -287,26 +361,96 @@ pub fn build_secp256r1_instruction(
     message: &[u8],
     signature: &[u8; 64],
 ) -> anchor_lang::solana_program::instruction::Instruction {
-    // Build instruction data
-    // Format: [num_sigs, sig_offset, sig_ix, pk_offset, pk_ix, msg_offset, msg_size, msg_ix, data...]
-    
-    let mut data = Vec::with_capacity(13 + 33 + 64 + message.len());
-    
-    // Header
-    data.push(1); // num_signatures = 1
-    data.extend_from_slice(&(13u16).to_le_bytes()); // signature_offset = after header
-    data.push(0xFF); // signature_ix_index = current instruction
-    data.extend_from_slice(&(77u16).to_le_bytes()); // pubkey_offset = after sig
-    data.push(0xFF); // pubkey_ix_index = current instruction
-    data.extend_from_slice(&(110u16).to_le_bytes()); // message_offset = after pk
-    data.extend_from_slice(&(message.len() as u16).to_le_bytes()); // message_size
-    data.push(0xFF); // message_ix_index = current instruction
-    
-    // Actual data
-    data.extend_from_slice(signature);
-    data.extend_from_slice(pubkey);
-    data.extend_from_slice(message);
-    
+    build_secp256r1_instruction_batch(&[(pubkey, message, signature)])
+}
+
+/// Build a single secp256r1 precompile instruction carrying `entries.len()`
+/// signatures, so an M-of-N threshold can be verified in one instruction
+/// instead of M. Each entry's pubkey/signature/message offsets are computed
+/// from the preceding entries' sizes rather than hardcoded.
+pub fn build_secp256r1_instruction_batch(
+    entries: &[(&[u8; 33], &[u8], &[u8; 64])],
+) -> anchor_lang::solana_program::instruction::Instruction {
+    let header_len = 2 + entries.len() * Secp256r1SignatureOffsets::ENCODED_SIZE;
+    let payload_len: usize = entries.iter().map(|(_, message, _)| 64 + 33 + message.len()).sum();
+
+    let mut data = Vec::with_capacity(header_len + payload_len);
+    data.push(entries.len() as u8); // num_signatures
+    data.push(0); // padding
+
+    // Lay out the offset table first, computing each entry's payload
+    // position from the running total of the entries before it.
+    let mut payload_offset = header_len;
+    for (_, message, _) in entries {
+        let sig_offset = payload_offset as u16;
+        let pk_offset = (payload_offset + 64) as u16;
+        let msg_offset = (payload_offset + 64 + 33) as u16;
+
+        data.extend_from_slice(&sig_offset.to_le_bytes());
+        data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // signature_ix_index
+        data.extend_from_slice(&pk_offset.to_le_bytes());
+        data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // pubkey_ix_index
+        data.extend_from_slice(&msg_offset.to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // message_ix_index
+
+        payload_offset += 64 + 33 + message.len();
+    }
+
+    for (pubkey, message, signature) in entries {
+        data.extend_from_slice(*signature);
+        data.extend_from_slice(*pubkey);
+        data.extend_from_slice(message);
+    }
+
+    anchor_lang::solana_program::instruction::Instruction {
+        program_id: SECP256R1_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Same as [`build_secp256r1_instruction_batch`], but the message for
+/// every entry is not inlined here: it lives at `message_offset` (size
+/// `message_size`) inside instruction `message_ix_index` of the same
+/// transaction. Pairs with the cross-instruction resolution on the
+/// program side, letting a client put one shared message in the execute
+/// instruction itself and reference it from the precompile instead of
+/// duplicating it per signature.
+pub fn build_secp256r1_instruction_batch_with_message_ref(
+    entries: &[(&[u8; 33], &[u8; 64])],
+    message_ix_index: u16,
+    message_offset: u16,
+    message_size: u16,
+) -> anchor_lang::solana_program::instruction::Instruction {
+    let header_len = 2 + entries.len() * Secp256r1SignatureOffsets::ENCODED_SIZE;
+    let payload_len = entries.len() * (64 + 33);
+
+    let mut data = Vec::with_capacity(header_len + payload_len);
+    data.push(entries.len() as u8); // num_signatures
+    data.push(0); // padding
+
+    let mut payload_offset = header_len;
+    for _ in entries {
+        let sig_offset = payload_offset as u16;
+        let pk_offset = (payload_offset + 64) as u16;
+
+        data.extend_from_slice(&sig_offset.to_le_bytes());
+        data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // signature_ix_index
+        data.extend_from_slice(&pk_offset.to_le_bytes());
+        data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // pubkey_ix_index
+        data.extend_from_slice(&message_offset.to_le_bytes());
+        data.extend_from_slice(&message_size.to_le_bytes());
+        data.extend_from_slice(&message_ix_index.to_le_bytes());
+
+        payload_offset += 64 + 33;
+    }
+
+    for (pubkey, signature) in entries {
+        data.extend_from_slice(*signature);
+        data.extend_from_slice(*pubkey);
+    }
+
     anchor_lang::solana_program::instruction::Instruction {
         program_id: SECP256R1_PROGRAM_ID,
         accounts: vec![],
@@ -317,24 +461,139 @@ pub fn build_secp256r1_instruction(
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
+    /// A minimal `AccountInfo` for exercising code paths that take the
+    /// instructions sysvar but don't actually read it - e.g. the `0xFFFF`
+    /// ("this instruction") branch of `resolve_instruction_data`/
+    /// `validate_offset`, which never touches `instructions_sysvar`.
+    fn dummy_account_info<'a>(
+        key: &'a Pubkey,
+        lamports: &'a mut u64,
+        data: &'a mut [u8],
+        owner: &'a Pubkey,
+    ) -> AccountInfo<'a> {
+        AccountInfo::new(key, false, false, lamports, data, owner, false, 0)
+    }
+
+    #[test]
+    fn test_resolve_instruction_data_returns_current_instruction_for_ffff_index() {
+        let key = Pubkey::default();
+        let owner = Pubkey::default();
+        let mut lamports = 0u64;
+        let mut data = [];
+        let ix_sysvar = dummy_account_info(&key, &mut lamports, &mut data, &owner);
+
+        let current_instruction_data = vec![1, 2, 3, 4];
+        let resolved =
+            resolve_instruction_data(0xFFFF, &current_instruction_data, &ix_sysvar).unwrap();
+        assert_eq!(resolved, current_instruction_data);
+    }
+
+    #[test]
+    fn test_validate_offset_rejects_overflowing_current_instruction() {
+        let key = Pubkey::default();
+        let owner = Pubkey::default();
+        let mut lamports = 0u64;
+        let mut data = [];
+        let ix_sysvar = dummy_account_info(&key, &mut lamports, &mut data, &owner);
+
+        // A 10-byte instruction can't hold a 64-byte signature starting at offset 5.
+        let current_instruction_data = vec![0u8; 10];
+        assert!(validate_offset(0xFFFF, 5, 64, &current_instruction_data, &ix_sysvar).is_err());
+    }
+
+    #[test]
+    fn test_validate_offset_accepts_offset_within_bounds() {
+        let key = Pubkey::default();
+        let owner = Pubkey::default();
+        let mut lamports = 0u64;
+        let mut data = [];
+        let ix_sysvar = dummy_account_info(&key, &mut lamports, &mut data, &owner);
+
+        let current_instruction_data = vec![0u8; 100];
+        assert!(validate_offset(0xFFFF, 5, 64, &current_instruction_data, &ix_sysvar).is_ok());
+    }
+
     #[test]
     fn test_parse_instruction_data() {
         let data = vec![
             1, // num_sigs
+            0, // padding
             13, 0, // sig_offset
-            0xFF, // sig_ix
+            0xFF, 0xFF, // sig_ix
             77, 0, // pk_offset
-            0xFF, // pk_ix
+            0xFF, 0xFF, // pk_ix
             110, 0, // msg_offset
             32, 0, // msg_size
-            0xFF, // msg_ix
+            0xFF, 0xFF, // msg_ix
         ];
-        
+
         let parsed = Secp256r1InstructionData::try_from_slice(&data).unwrap();
         assert_eq!(parsed.num_signatures, 1);
-        assert_eq!(parsed.signature_offset, 13);
-        assert_eq!(parsed.message_size, 32);
+        assert_eq!(parsed.offsets.len(), 1);
+        assert_eq!(parsed.offsets[0].signature_offset, 13);
+        assert_eq!(parsed.offsets[0].message_size, 32);
     }
-}
 
+    #[test]
+    fn test_parse_multi_signature_instruction_data() {
+        let mut data = vec![2, 0]; // num_sigs = 2, padding
+        // Two offset structs back to back
+        for sig_offset in [16u16, 96u16] {
+            data.extend_from_slice(&sig_offset.to_le_bytes()); // signature_offset
+            data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // signature_ix_index
+            data.extend_from_slice(&(sig_offset + 64).to_le_bytes()); // pubkey_offset
+            data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // pubkey_ix_index
+            data.extend_from_slice(&(sig_offset + 64 + 33).to_le_bytes()); // message_offset
+            data.extend_from_slice(&32u16.to_le_bytes()); // message_size
+            data.extend_from_slice(&0xFFFFu16.to_le_bytes()); // message_ix_index
+        }
+
+        let parsed = Secp256r1InstructionData::try_from_slice(&data).unwrap();
+        assert_eq!(parsed.num_signatures, 2);
+        assert_eq!(parsed.offsets.len(), 2);
+        assert_eq!(parsed.offsets[0].signature_offset, 16);
+        assert_eq!(parsed.offsets[1].signature_offset, 96);
+    }
+
+    #[test]
+    fn test_zero_signatures_rejected() {
+        let data = vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert!(Secp256r1InstructionData::try_from_slice(&data).is_err());
+    }
+
+    #[test]
+    fn test_build_batch_instruction_round_trips_through_parser() {
+        let pk_a = [0x02u8; 33];
+        let pk_b = [0x03u8; 33];
+        let sig_a = [1u8; 64];
+        let sig_b = [2u8; 64];
+        let msg_a = b"first message".to_vec();
+        let msg_b = b"second, slightly longer message".to_vec();
+
+        let ix = build_secp256r1_instruction_batch(&[
+            (&pk_a, &msg_a, &sig_a),
+            (&pk_b, &msg_b, &sig_b),
+        ]);
+
+        let parsed = Secp256r1InstructionData::try_from_slice(&ix.data).unwrap();
+        assert_eq!(parsed.num_signatures, 2);
+        assert_eq!(parsed.offsets.len(), 2);
+
+        // All offsets/sizes should point at in-instruction payload
+        // slices that exactly reproduce what was passed in.
+        for (offsets, (pubkey, message, signature)) in parsed.offsets.iter().zip([
+            (&pk_a, &msg_a, &sig_a),
+            (&pk_b, &msg_b, &sig_b),
+        ]) {
+            let sig_start = offsets.signature_offset as usize;
+            let pk_start = offsets.pubkey_offset as usize;
+            let msg_start = offsets.message_offset as usize;
+            let msg_end = msg_start + offsets.message_size as usize;
+
+            assert_eq!(&ix.data[sig_start..sig_start + 64], signature.as_slice());
+            assert_eq!(&ix.data[pk_start..pk_start + 33], pubkey.as_slice());
+            assert_eq!(&ix.data[msg_start..msg_end], message.as_slice());
+        }
+    }
+}
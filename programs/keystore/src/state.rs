@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use crate::error::KeystoreError;
+
+/// An identity account owns a vault PDA and holds the set of keys
+/// authorized to sign on its behalf, plus the threshold required to
+/// execute an action.
+#[account]
+pub struct Identity {
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub threshold: u8,
+    pub nonce: u64,
+    pub keys: Vec<RegisteredKey>,
+    /// Maximum lamports `Action::Send` may move per `window_secs`. Zero means
+    /// unlimited, preserving the original unrestricted behavior.
+    pub spend_limit: u64,
+    pub window_secs: i64,
+    /// Unix timestamp the current spending window started at.
+    pub window_start: i64,
+    /// Lamports already sent within the current window.
+    pub spent_in_window: u64,
+    /// SHA256 of the WebAuthn RP's expected origin (e.g. `https://app.example.com`),
+    /// set at creation and checked against `clientDataJSON.origin` on every assertion.
+    pub allowed_origin_hash: [u8; 32],
+}
+
+impl Identity {
+    pub const MAX_KEYS: usize = 5;
+    pub const SIZE: usize = 1 // bump
+        + 1 // vault_bump
+        + 1 // threshold
+        + 8 // nonce
+        + 4 + Self::MAX_KEYS * RegisteredKey::SIZE // keys
+        + 8 // spend_limit
+        + 8 // window_secs
+        + 8 // window_start
+        + 8 // spent_in_window
+        + 32; // allowed_origin_hash
+}
+
+/// Which precompile verifies signatures from a [`RegisteredKey`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyType {
+    /// 33-byte compressed key, verified via the secp256r1 precompile
+    /// (WebAuthn/passkey devices).
+    Secp256r1,
+    /// 32-byte key, verified via the native ed25519 program (a plain
+    /// Solana keypair acting as a device without passkey hardware).
+    Ed25519,
+}
+
+/// A single key registered against an `Identity`, e.g. a WebAuthn
+/// passkey's public key. `pubkey` always holds 33 bytes on the wire;
+/// for `KeyType::Ed25519` only the first 32 are significant and the
+/// last must be zero.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RegisteredKey {
+    pub key_type: KeyType,
+    pub pubkey: [u8; 33],
+    pub name: String,
+    pub added_at: i64,
+    /// Highest WebAuthn authenticator `signCount` seen for this key, used
+    /// to detect cloned passkeys. Authenticators that don't implement a
+    /// counter always report 0, so a count of 0 is accepted while the
+    /// stored count is also 0; otherwise the count must strictly increase.
+    pub last_sign_count: u32,
+}
+
+impl RegisteredKey {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const SIZE: usize = 1 // key_type
+        + 33 // pubkey
+        + 4 + Self::MAX_NAME_LEN // name
+        + 8 // added_at
+        + 4; // last_sign_count
+
+    /// The 32-byte ed25519 public key, for keys of that type.
+    pub fn ed25519_pubkey(&self) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&self.pubkey[..32]);
+        key
+    }
+
+    /// Validate a raw 33-byte wire pubkey against the rules for `key_type`.
+    pub fn validate_pubkey(key_type: KeyType, pubkey: &[u8; 33]) -> Result<()> {
+        match key_type {
+            KeyType::Secp256r1 => require!(
+                pubkey[0] == 0x02 || pubkey[0] == 0x03,
+                KeystoreError::InvalidPublicKeyFormat
+            ),
+            KeyType::Ed25519 => require!(
+                pubkey[32] == 0,
+                KeystoreError::InvalidPublicKeyFormat
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// Records the WebAuthn credential id for a single registered key so
+/// the frontend can look it up via `navigator.credentials.get`.
+#[account]
+pub struct CredentialRegistry {
+    pub bump: u8,
+    pub identity: Pubkey,
+    pub key_index: u8,
+    pub credential_id: Vec<u8>,
+    pub device_name: String,
+    pub registered_at: i64,
+}
+
+impl CredentialRegistry {
+    pub const MAX_CREDENTIAL_ID_LEN: usize = 256;
+    pub const MAX_DEVICE_NAME_LEN: usize = 32;
+    pub const SIZE: usize = 1 // bump
+        + 32 // identity
+        + 1 // key_index
+        + 4 + Self::MAX_CREDENTIAL_ID_LEN // credential_id
+        + 4 + Self::MAX_DEVICE_NAME_LEN // device_name
+        + 8; // registered_at
+}